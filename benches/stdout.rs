@@ -0,0 +1,60 @@
+//! Compares the throughput of writing lines to the standard output stream.
+//!
+//! The standard output stream is written to directly and through [`carli::io::Stream`] using each
+//! of the buffering strategies. Since the lines are written to `STDOUT`, it should be redirected
+//! to avoid measuring the speed of the terminal instead:
+//!
+//! ```text
+//! cargo bench --bench stdout > /dev/null
+//! ```
+//!
+//! The results are written to `STDERR`.
+
+use carli::io::{Buffering, Stream};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// The number of lines written for each measurement.
+const LINES: usize = 1_000_000;
+
+/// Measures how long it takes to write the lines using the given writer.
+fn measure<W: Write>(mut writer: W) -> Duration {
+    let start = Instant::now();
+
+    for i in 0..LINES {
+        writeln!(writer, "This is line number {} of the benchmark.", i).unwrap();
+    }
+
+    writer.flush().unwrap();
+
+    start.elapsed()
+}
+
+/// Reports the results of a measurement.
+fn report(name: &str, elapsed: Duration, baseline: Duration) {
+    eprintln!(
+        "{:<24} {:>10.2?} {:>12.0} lines/s {:>6.2}x",
+        name,
+        elapsed,
+        LINES as f64 / elapsed.as_secs_f64(),
+        baseline.as_secs_f64() / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let baseline = measure(io::stdout());
+
+    report("std::io::Stdout", baseline, baseline);
+
+    for (name, buffering) in [
+        ("Stream (unbuffered)", Buffering::Unbuffered),
+        ("Stream (line)", Buffering::Line),
+        ("Stream (block)", Buffering::Block),
+    ] {
+        let mut stream: Stream = io::stdout().into();
+
+        stream.set_buffering(buffering).unwrap();
+
+        report(name, measure(stream), baseline);
+    }
+}
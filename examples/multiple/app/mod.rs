@@ -53,15 +53,15 @@ impl Main for Application {
 }
 
 impl Shared for Application {
-    fn error(&self) -> std::cell::RefMut<Stream> {
+    fn error(&self) -> std::cell::RefMut<'_, Stream> {
         self.error.borrow_mut()
     }
 
-    fn input(&self) -> std::cell::RefMut<Stream> {
+    fn input(&self) -> std::cell::RefMut<'_, Stream> {
         self.input.borrow_mut()
     }
 
-    fn output(&self) -> std::cell::RefMut<Stream> {
+    fn output(&self) -> std::cell::RefMut<'_, Stream> {
         self.output.borrow_mut()
     }
 }
@@ -66,7 +66,6 @@ fn main() {
 mod test {
     use super::{example, Application};
     use carli::prelude::test::*;
-    use std::io::{Seek, SeekFrom};
 
     /// Verifies that when the `error` flag is used, the function returns a failing response.
     #[test]
@@ -89,12 +88,7 @@ mod test {
         assert_eq!(error.get_status(), 1);
 
         // Make sure the expected error output was written.
-        streams.error().seek(SeekFrom::Start(0)).unwrap();
-
-        assert_eq!(
-            streams.error().to_string_lossy(),
-            "The command is about to fail!\n"
-        );
+        assert_eq!(streams.error_string(), "The command is about to fail!\n");
     }
 
     /// Verifies that the function returns a successful response if the `error` flag is not used.
@@ -113,8 +107,6 @@ mod test {
         assert!(result.is_ok());
 
         // Make sure the expected output was written.
-        streams.output().seek(SeekFrom::Start(0)).unwrap();
-
-        assert_eq!(streams.output().to_string_lossy(), "Hello, world!\n");
+        assert_eq!(streams.output_string(), "Hello, world!\n");
     }
 }
@@ -179,6 +179,153 @@ pub trait Main: io::Shared + Sized {
     fn subcommand(&self) -> &dyn Execute<Self>;
 }
 
+/// The future returned when executing a command asynchronously.
+///
+/// Since the traits for asynchronous commands have to be usable as trait objects, the future is
+/// boxed. An implementation usually wraps an `async` block using [`Box::pin`]. The future must be
+/// [`Send`], so that it can be spawned on a multi-threaded runtime using `tokio::spawn`.
+#[cfg(feature = "tokio")]
+pub type BoxFuture<'a> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = error::Result<()>> + Send + 'a>>;
+
+/// A trait for objects which can be executed as asynchronous commands in an application.
+///
+/// This is the counterpart to [`Execute`] for commands that need to `.await` things, such as
+/// network or file I/O. The context implements [`io::AsyncShared`], which makes it possible to use
+/// [`io::AsyncStreams`] with in-memory buffers when testing. The command must be [`Sync`], since
+/// the future it returns borrows it and must be [`Send`] (see [`BoxFuture`]).
+///
+/// ```
+/// use carli::command::{AsyncExecute, BoxFuture};
+/// use carli::io::{AsyncShared, AsyncStreams};
+/// use tokio::io::AsyncWriteExt;
+///
+/// /// An example subcommand.
+/// struct Subcommand {
+///     /// A value specific to this subcommand.
+///     name: String,
+/// }
+///
+/// impl AsyncExecute<AsyncStreams> for Subcommand {
+///     fn execute<'a>(&'a self, context: &'a AsyncStreams) -> BoxFuture<'a> {
+///         Box::pin(async move {
+///             let message = format!("Hello, {}!\n", self.name);
+///
+///             let mut output = context.output().lock().await;
+///
+///             output.write_all(message.as_bytes()).await?;
+///
+///             Ok(())
+///         })
+///     }
+/// }
+/// ```
+#[cfg(feature = "tokio")]
+pub trait AsyncExecute<T>: Sync
+where
+    T: io::AsyncShared,
+{
+    /// Executes the command asynchronously using the given context.
+    ///
+    /// See [`Execute::execute`] for more information.
+    fn execute<'a>(&'a self, context: &'a T) -> BoxFuture<'a>;
+}
+
+/// A trait for objects which manage the context for asynchronous application subcommands.
+///
+/// This is the counterpart to [`Main`] for applications with subcommands that use
+/// [`AsyncExecute`]. The future that is returned is meant to be awaited by the entrypoint of the
+/// application, such as one using `#[tokio::main]`.
+///
+/// ```
+/// use carli::command::{AsyncExecute, AsyncMain, BoxFuture};
+/// use carli::io::{AsyncShared, AsyncStream, AsyncStreams};
+/// use tokio::io::AsyncWriteExt;
+/// use tokio::sync::Mutex;
+///
+/// /// An example context.
+/// struct Application {
+///     /// The streams for the application.
+///     streams: AsyncStreams,
+///
+///     /// The user requested subcommand.
+///     subcommand: Hello,
+/// }
+///
+/// impl AsyncMain for Application {
+///     fn subcommand(&self) -> &dyn AsyncExecute<Self> {
+///         &self.subcommand
+///     }
+/// }
+///
+/// impl AsyncShared for Application {
+///     fn error(&self) -> &Mutex<AsyncStream> {
+///         self.streams.error()
+///     }
+///
+///     fn input(&self) -> &Mutex<AsyncStream> {
+///         self.streams.input()
+///     }
+///
+///     fn output(&self) -> &Mutex<AsyncStream> {
+///         self.streams.output()
+///     }
+/// }
+///
+/// /// Say hello to the user.
+/// struct Hello;
+///
+/// impl AsyncExecute<Application> for Hello {
+///     fn execute<'a>(&'a self, context: &'a Application) -> BoxFuture<'a> {
+///         Box::pin(async move {
+///             let mut output = context.output().lock().await;
+///
+///             output.write_all(b"Hello, world!\n").await?;
+///
+///             Ok(())
+///         })
+///     }
+/// }
+///
+/// async fn run() {
+///     let app = Application {
+///         streams: AsyncStreams::standard(),
+///         subcommand: Hello,
+///     };
+///
+///     if let Err(error) = app.execute().await {
+///         error.exit();
+///     }
+/// }
+/// ```
+#[cfg(feature = "tokio")]
+pub trait AsyncMain: io::AsyncShared + Sized {
+    /// Executes the requested subcommand for the application.
+    ///
+    /// Once the subcommand has finished, the error and global output streams are flushed, since
+    /// anything written to the standard streams provided by [`tokio`] may otherwise be lost when
+    /// the process exits. An error from the subcommand takes precedence over one from flushing.
+    fn execute(&self) -> BoxFuture<'_> {
+        Box::pin(async move {
+            use tokio::io::AsyncWriteExt;
+
+            let result = self.subcommand().execute(self).await;
+
+            let flushed = match self.error().lock().await.flush().await {
+                Ok(()) => self.output().lock().await.flush().await,
+                Err(error) => Err(error),
+            };
+
+            result?;
+
+            Ok(flushed?)
+        })
+    }
+
+    /// Returns the subcommand to be executed.
+    fn subcommand(&self) -> &dyn AsyncExecute<Self>;
+}
+
 /// Simplifies writing a line terminated string to the error output.
 ///
 /// ### Writing a simple message
@@ -266,7 +413,7 @@ mod test {
     use super::*;
     use crate::io::{memory, Shared};
     use std::cell;
-    use std::io::{Seek, Write};
+    use std::io::Write;
 
     /// An example application context.
     struct Application {
@@ -306,15 +453,15 @@ mod test {
     }
 
     impl io::Shared for Application {
-        fn error(&self) -> cell::RefMut<io::Stream> {
+        fn error(&self) -> cell::RefMut<'_, io::Stream> {
             self.error.borrow_mut()
         }
 
-        fn input(&self) -> cell::RefMut<io::Stream> {
+        fn input(&self) -> cell::RefMut<'_, io::Stream> {
             self.input.borrow_mut()
         }
 
-        fn output(&self) -> cell::RefMut<io::Stream> {
+        fn output(&self) -> cell::RefMut<'_, io::Stream> {
             self.output.borrow_mut()
         }
     }
@@ -359,17 +506,68 @@ mod test {
         }
     }
 
+    /// An example asynchronous application context.
+    #[cfg(feature = "tokio")]
+    struct AsyncApplication {
+        /// The streams for the application.
+        streams: io::AsyncStreams,
+    }
+
+    #[cfg(feature = "tokio")]
+    impl AsyncMain for AsyncApplication {
+        fn subcommand(&self) -> &dyn AsyncExecute<Self> {
+            &Echo {}
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    impl io::AsyncShared for AsyncApplication {
+        fn error(&self) -> &tokio::sync::Mutex<io::AsyncStream> {
+            self.streams.error()
+        }
+
+        fn input(&self) -> &tokio::sync::Mutex<io::AsyncStream> {
+            self.streams.input()
+        }
+
+        fn output(&self) -> &tokio::sync::Mutex<io::AsyncStream> {
+            self.streams.output()
+        }
+    }
+
+    /// An example asynchronous subcommand that echoes its input.
+    #[cfg(feature = "tokio")]
+    struct Echo {}
+
+    #[cfg(feature = "tokio")]
+    impl AsyncExecute<AsyncApplication> for Echo {
+        fn execute<'a>(&'a self, context: &'a AsyncApplication) -> BoxFuture<'a> {
+            Box::pin(async move {
+                use io::AsyncShared;
+                use tokio::io::AsyncWriteExt;
+
+                let input = context.input().lock().await.to_string().await?;
+
+                if input.is_empty() {
+                    crate::err!(1, "Nothing to echo.");
+                }
+
+                let mut output = context.output().lock().await;
+
+                output.write_all(input.as_bytes()).await?;
+
+                Ok(())
+            })
+        }
+    }
+
     #[test]
     fn errorln_message() {
         let streams = memory();
 
         errorln!(streams, "test").unwrap();
 
-        let mut error = streams.error();
-
-        error.rewind().unwrap();
-
-        assert_eq!(error.to_string_lossy(), "test\n");
+        assert_eq!(streams.error_string(), "test\n");
     }
 
     #[test]
@@ -378,11 +576,7 @@ mod test {
 
         errorln!(streams, "test {}", "message").unwrap();
 
-        let mut error = streams.error();
-
-        error.rewind().unwrap();
-
-        assert_eq!(error.to_string_lossy(), "test message\n");
+        assert_eq!(streams.error_string(), "test message\n");
     }
 
     #[test]
@@ -391,11 +585,7 @@ mod test {
 
         app.execute().unwrap();
 
-        let mut output = app.output();
-
-        output.rewind().unwrap();
-
-        assert_eq!(output.to_string_lossy(), "Goodbye, world.\n");
+        assert_eq!(app.output().contents(), Some(&b"Goodbye, world.\n"[..]));
     }
 
     #[test]
@@ -404,11 +594,55 @@ mod test {
 
         app.execute().unwrap();
 
-        let mut output = app.output();
+        assert_eq!(app.output().contents(), Some(&b"Hello, world!\n"[..]));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn execute_async() {
+        use crate::error::Inspect;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let app = AsyncApplication {
+            streams: io::AsyncStreams::memory_with_input("test"),
+        };
+
+        runtime.block_on(app.execute()).unwrap();
+
+        assert_eq!(app.streams.output_string(), "test");
 
-        output.rewind().unwrap();
+        let app = AsyncApplication {
+            streams: io::AsyncStreams::memory(),
+        };
 
-        assert_eq!(output.to_string_lossy(), "Hello, world!\n");
+        let error = runtime.block_on(app.execute()).unwrap_err();
+
+        assert_eq!(error.get_message(), Some("Nothing to echo."));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn execute_async_spawned() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let app = std::sync::Arc::new(AsyncApplication {
+            streams: io::AsyncStreams::memory_with_input("test"),
+        });
+
+        let task = {
+            let app = app.clone();
+
+            runtime.spawn(async move { app.execute().await })
+        };
+
+        runtime.block_on(task).unwrap().unwrap();
+
+        assert_eq!(app.streams.output_string(), "test");
     }
 
     #[test]
@@ -417,11 +651,7 @@ mod test {
 
         outputln!(streams, "test").unwrap();
 
-        let mut output = streams.output();
-
-        output.rewind().unwrap();
-
-        assert_eq!(output.to_string_lossy(), "test\n");
+        assert_eq!(streams.output_string(), "test\n");
     }
 
     #[test]
@@ -430,10 +660,6 @@ mod test {
 
         outputln!(streams, "test {}", "message").unwrap();
 
-        let mut output = streams.output();
-
-        output.rewind().unwrap();
-
-        assert_eq!(output.to_string_lossy(), "test message\n");
+        assert_eq!(streams.output_string(), "test message\n");
     }
 }
@@ -153,6 +153,9 @@ pub struct Error {
     /// The original error message.
     message: Option<String>,
 
+    /// The flag used to prevent the error from being printed when exiting.
+    quiet: bool,
+
     /// The exit status code.
     status: i32,
 }
@@ -162,6 +165,7 @@ impl Default for Error {
         Self {
             context: None,
             message: None,
+            quiet: false,
             status: 1,
         }
     }
@@ -196,7 +200,12 @@ impl Error {
     ///
     /// When the application has reached a point where the only remaining task is to exit, this
     /// method may be called to print the error and context messages to `STDERR` and finally exit
-    /// with the appropriate exit status code.
+    /// with the appropriate exit status code. Anything still buffered for the standard streams is
+    /// written before the error is printed (see [`crate::io::flush_standard`]). Nothing is printed
+    /// if the error is quiet, such as a broken pipe handled by [`crate::io::BrokenPipe::Quiet`].
+    /// Any temporary files that were not committed are also removed (see
+    /// [`crate::io::discard_uncommitted`]), and any pager is closed and waited on before the error
+    /// is printed (see [`crate::io::wait_for_pagers`]).
     ///
     /// ```no_run
     /// # use carli::error::Error;
@@ -207,7 +216,12 @@ impl Error {
     /// # }
     /// ```
     pub fn exit(self) -> ! {
-        if self.context.is_some() || self.message.is_some() {
+        let _ = crate::io::flush_standard();
+
+        crate::io::discard_uncommitted();
+        crate::io::wait_for_pagers();
+
+        if !self.quiet && (self.context.is_some() || self.message.is_some()) {
             eprintln!("{}", self);
         }
 
@@ -240,6 +254,7 @@ impl Error {
         Self {
             context: None,
             message: None,
+            quiet: false,
             status,
         }
     }
@@ -270,6 +285,7 @@ impl<T: std::error::Error + 'static> From<T> for Error {
         let mut context = None;
         let mut current = &error as &dyn std::error::Error;
         let message;
+        let mut quiet = false;
         let mut status = 1;
 
         // Allow for error source traversal.
@@ -288,8 +304,16 @@ impl<T: std::error::Error + 'static> From<T> for Error {
 
                 // If std::io::Error, capture the OS error code as the status.
                 if let Some(other) = current.downcast_ref::<std::io::Error>() {
-                    if let Some(code) = other.raw_os_error() {
-                        status = code;
+                    let pipe = other
+                        .get_ref()
+                        .and_then(|inner| inner.downcast_ref::<crate::io::QuietPipe>());
+
+                    // Unless it is a broken pipe that should be handled quietly.
+                    if let Some(pipe) = pipe {
+                        quiet = true;
+                        status = pipe.status;
+                    } else {
+                        status = io_status(other).unwrap_or(status);
                     }
                 }
 
@@ -300,11 +324,51 @@ impl<T: std::error::Error + 'static> From<T> for Error {
         Self {
             context,
             message,
+            quiet,
             status,
         }
     }
 }
 
+/// The OS error code used when an operation is not supported (`ENOTSUP`).
+#[cfg(unix)]
+const ENOTSUP: i32 = libc::ENOTSUP;
+
+/// The OS error code used when an operation is not supported (`ERROR_NOT_SUPPORTED`).
+#[cfg(not(unix))]
+const ENOTSUP: i32 = 50;
+
+/// The OS error code used when a file is too large (`EFBIG`).
+#[cfg(unix)]
+const EFBIG: i32 = libc::EFBIG;
+
+/// The OS error code used when a file is too large (`ERROR_FILE_TOO_LARGE`).
+#[cfg(not(unix))]
+const EFBIG: i32 = 223;
+
+/// Returns the exit status code for an I/O error, if one can be determined.
+///
+/// The OS error code is used when the error originated from the operating system. Errors that are
+/// created by this library (e.g. an unsupported [`crate::io::Stream`] operation) do not have one,
+/// so the OS error code that is closest in meaning to the kind of error is used instead.
+fn io_status(error: &std::io::Error) -> Option<i32> {
+    if let Some(code) = error.raw_os_error() {
+        return Some(code);
+    }
+
+    if error
+        .get_ref()
+        .is_some_and(|inner| inner.is::<crate::io::TooLarge>())
+    {
+        return Some(EFBIG);
+    }
+
+    match error.kind() {
+        std::io::ErrorKind::Unsupported => Some(ENOTSUP),
+        _ => None,
+    }
+}
+
 /// A trait for inspecting the contents of error with exit statuses.
 ///
 /// When this trait is brought into scope, access to the inner context, message, and status code
@@ -400,6 +464,24 @@ pub trait Inspect {
     /// }
     /// ```
     fn get_status(&self) -> i32;
+
+    /// Checks if the error is not printed when exiting.
+    ///
+    /// The default implementation returns `false`, so that types implementing this trait before
+    /// quiet errors existed keep working.
+    ///
+    /// ```
+    /// use carli::error::{Error, Inspect};
+    ///
+    /// # fn main() {
+    /// let error = Error::new(1).message("An example error.");
+    ///
+    /// assert!(!error.is_quiet());
+    /// # }
+    /// ```
+    fn is_quiet(&self) -> bool {
+        false
+    }
 }
 
 impl Inspect for Error {
@@ -416,6 +498,10 @@ impl Inspect for Error {
     fn get_status(&self) -> i32 {
         self.status
     }
+
+    fn is_quiet(&self) -> bool {
+        self.quiet
+    }
 }
 
 /// A specialized [`Result`] that may be an error with an exit status.
@@ -659,6 +745,54 @@ mod test {
         }
     }
 
+    #[test]
+    fn from_quiet_broken_pipe_error() {
+        use crate::io::{BrokenPipe, Faulty, Stream};
+        use std::io::Write;
+
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .fail_write(1, std::io::Error::from_raw_os_error(32))
+            .into();
+
+        let error = Error::from(write!(stream, "test").unwrap_err());
+
+        assert!(!error.quiet);
+        assert_eq!(error.status, 32);
+
+        stream.set_broken_pipe(BrokenPipe::Quiet(141));
+
+        let error = Error::from(write!(stream, "test").unwrap_err()).context("The context.");
+
+        assert!(error.quiet);
+        assert_eq!(error.status, 141);
+    }
+
+    #[test]
+    fn from_too_large_error() {
+        let mut stream: crate::io::Stream = b"test".to_vec().into();
+        let error = Error::from(stream.to_string_limited(3).unwrap_err());
+
+        assert_eq!(
+            error.message,
+            Some("The stream is larger than the limit of 3 bytes.".to_string())
+        );
+        assert_eq!(error.status, EFBIG);
+    }
+
+    #[test]
+    fn from_unsupported_error() {
+        let error = Error::from(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "The stream does not support reading.",
+        ));
+
+        assert_eq!(
+            error.message,
+            Some("The stream does not support reading.".to_string())
+        );
+        assert_eq!(error.status, ENOTSUP);
+    }
+
     #[test]
     fn result_context() {
         let err: Result<()> = Err(Error::default()).context(|| "The context message.");
@@ -61,7 +61,33 @@
 //!     example(streams).unwrap();
 //! }
 //! ```
-use std::{cell, io};
+#[cfg(feature = "tokio")]
+mod asynchronous;
+mod atomic;
+#[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+mod compress;
+mod encoding;
+mod fault;
+mod locked;
+mod pager;
+mod records;
+mod stdio;
+
+#[cfg(feature = "tokio")]
+pub use asynchronous::{AsyncShared, AsyncStream, AsyncStreams};
+pub use atomic::discard_uncommitted;
+#[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+pub use compress::Compression;
+pub use encoding::{Encoding, Text};
+pub use fault::Faulty;
+pub use locked::{SyncShared, SyncStreams};
+pub use pager::wait_for_pagers;
+pub use records::{Chunks, Lines, Split};
+pub use stdio::{flush_standard, Buffering};
+
+use crate::error::{self, Error};
+use crate::style;
+use std::{cell, collections, fmt, fs, io, path};
 
 /// A trait for objects that manage the shared input and output streams for a command.
 ///
@@ -90,7 +116,7 @@ pub trait Shared {
     ///     Ok(())
     /// }
     /// ```
-    fn error(&self) -> cell::RefMut<Stream>;
+    fn error(&self) -> cell::RefMut<'_, Stream>;
 
     /// Returns the input stream.
     ///
@@ -106,7 +132,30 @@ pub trait Shared {
     ///     Ok(())
     /// }
     /// ```
-    fn input(&self) -> cell::RefMut<Stream>;
+    fn input(&self) -> cell::RefMut<'_, Stream>;
+
+    /// Checks if the user can be interacted with.
+    ///
+    /// The user is considered to be interactive if both the input and error output streams are
+    /// connected to a terminal, which means that it should be safe to prompt for input. Neither
+    /// stream may be borrowed when this method is called.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Shared;
+    /// use std::io::Write;
+    ///
+    /// fn example(streams: &dyn Shared) -> Result<()> {
+    ///     if streams.is_interactive() {
+    ///         writeln!(streams.error(), "What is your name?")?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    fn is_interactive(&self) -> bool {
+        self.input().is_terminal() && self.error().is_terminal()
+    }
 
     /// Returns the global output stream.
     ///
@@ -121,23 +170,383 @@ pub trait Shared {
     ///     Ok(())
     /// }
     /// ```
-    fn output(&self) -> cell::RefMut<Stream>;
+    fn output(&self) -> cell::RefMut<'_, Stream>;
+}
+
+/// The policies for handling a broken pipe when writing to a [`Stream`].
+///
+/// When the output of an application is piped to another process that stops reading early, such
+/// as `head`, writing to the output fails with [`io::ErrorKind::BrokenPipe`]. Reporting that as an
+/// error is rarely useful, so well behaved applications exit quietly instead. Since the error is
+/// usually propagated with `?`, a quiet policy marks the error so that it is not printed when
+/// [`crate::error::Error::exit`] is called, and uses the given exit status code instead of the OS
+/// error code. The status is conventionally `141` (what a shell reports when the process would
+/// have been terminated by `SIGPIPE`), or `0`.
+///
+/// ```no_run
+/// use carli::io::{self, BrokenPipe};
+/// use carli::outputln;
+///
+/// # fn main() {
+/// let streams = io::standard();
+///
+/// streams.set_broken_pipe(BrokenPipe::Quiet(141));
+///
+/// for i in 0.. {
+///     if let Err(error) = outputln!(streams, "Line {}", i) {
+///         carli::error::Error::from(error).exit();
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BrokenPipe {
+    /// Exits quietly with the given exit status code.
+    Quiet(i32),
+
+    /// Reports the broken pipe like any other error.
+    #[default]
+    Report,
+}
+
+/// The operations supported by a [`Stream`].
+///
+/// Not every stream supports every operation. For example, [`io::Stdin`] cannot be written to and
+/// [`io::Stdout`] cannot be read from. The capabilities of a stream may be checked before acting
+/// on it so that generic code can choose a different course of action instead of handling the
+/// [`io::ErrorKind::Unsupported`] error that would otherwise be returned.
+///
+/// ```
+/// use carli::io::Stream;
+/// use std::io;
+///
+/// # fn main() {
+/// let stream: Stream = io::stdout().into();
+/// let capabilities = stream.capabilities();
+///
+/// assert!(!capabilities.is_readable());
+/// assert!(!capabilities.is_seekable());
+/// assert!(capabilities.is_writable());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Capabilities {
+    /// The flag used to indicate that the stream can be read from.
+    readable: bool,
+
+    /// The flag used to indicate that the stream position can be changed.
+    seekable: bool,
+
+    /// The flag used to indicate that the stream can be written to.
+    writable: bool,
+}
+
+impl Capabilities {
+    /// Returns `true` if the stream can be read from.
+    pub fn is_readable(&self) -> bool {
+        self.readable
+    }
+
+    /// Returns `true` if the stream position can be changed.
+    pub fn is_seekable(&self) -> bool {
+        self.seekable
+    }
+
+    /// Returns `true` if the stream can be written to and flushed.
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+}
+
+/// A trait for custom backends that support reading, writing, and seeking.
+///
+/// This trait is implemented for any type that implements [`io::Read`], [`io::Write`], and
+/// [`io::Seek`], and can be sent between threads. It exists so that a single trait object can be
+/// used by [`Stream::custom`] to support all three operations.
+pub trait ReadWriteSeek: io::Read + io::Write + io::Seek + Send {}
+
+impl<T: io::Read + io::Write + io::Seek + Send> ReadWriteSeek for T {}
+
+/// The custom backends that are supported.
+enum Custom {
+    /// Uses a custom reader for reading.
+    Read(Box<dyn io::Read + Send>),
+
+    /// Uses a custom backend for reading, writing, and seeking.
+    ReadWriteSeek(Box<dyn ReadWriteSeek>),
+
+    /// Uses a custom writer for writing.
+    Write(Box<dyn io::Write + Send>),
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(_) => f.write_str("Read"),
+            Self::ReadWriteSeek(_) => f.write_str("ReadWriteSeek"),
+            Self::Write(_) => f.write_str("Write"),
+        }
+    }
+}
+
+/// The policies for handling a failing stream that is part of a tee.
+///
+/// ```
+/// use carli::io::{Stream, TeePolicy};
+/// use std::io;
+///
+/// # fn main() {
+/// let stream = Stream::tee(
+///     vec![io::stdout().into(), Vec::new().into()],
+///     TeePolicy::BestEffort,
+/// );
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TeePolicy {
+    /// Keep using the remaining streams, and only fail if every stream has failed.
+    BestEffort,
+
+    /// Stop and return the error of the first stream that has failed.
+    #[default]
+    FailFast,
+}
+
+/// A collection of input files that are read one after the other.
+///
+/// Each file is only opened once the one before it has been read to the end, so that reading a
+/// large number of files does not need as many file descriptors.
+#[derive(Debug)]
+struct Concat {
+    /// The file being read, and its path.
+    current: Option<(path::PathBuf, Stream)>,
+
+    /// The paths of the files that have yet to be opened.
+    paths: collections::VecDeque<path::PathBuf>,
+}
+
+impl io::Read for Concat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.current.is_none() {
+                let path = match self.paths.pop_front() {
+                    Some(path) => path,
+                    None => return Ok(0),
+                };
+
+                let stream = if is_standard(&path) {
+                    io::stdin().into()
+                } else {
+                    Stream::open(&path).map_err(|error| InputFile::error("open", &path, error))?
+                };
+
+                self.current = Some((path, stream));
+            }
+
+            if let Some((path, stream)) = &mut self.current {
+                let read = stream
+                    .read(buf)
+                    .map_err(|error| InputFile::error("read", path, error))?;
+
+                if read > 0 || buf.is_empty() {
+                    return Ok(read);
+                }
+            }
+
+            self.current = None;
+        }
+    }
+}
+
+/// A collection of streams that are all written to at the same time.
+#[derive(Debug)]
+struct Tee {
+    /// The policy for handling a failing stream.
+    policy: TeePolicy,
+
+    /// The streams being written to.
+    sinks: Vec<Stream>,
+}
+
+impl Tee {
+    /// Applies an operation to every stream according to the policy.
+    fn apply<F>(&mut self, mut operation: F) -> io::Result<()>
+    where
+        F: FnMut(&mut Stream) -> io::Result<()>,
+    {
+        let mut error = None;
+        let mut failed = 0;
+
+        for sink in &mut self.sinks {
+            if let Err(next) = operation(sink) {
+                if self.policy == TeePolicy::FailFast {
+                    return Err(next);
+                }
+
+                error.get_or_insert(next);
+                failed += 1;
+            }
+        }
+
+        match error {
+            Some(error) if failed == self.sinks.len() => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// An in-memory buffer with separate positions for reading and writing.
+///
+/// The buffer behaves like a pipe: anything written is added at the write position, and reading
+/// starts at the read position, so data can be written and then read back without seeking first.
+/// Seeking moves both positions, relative to the read position, to the same place.
+///
+/// Chunks may also be queued to simulate input that arrives over time. A queued chunk is only
+/// added to the end of the buffer once everything before it has been read.
+#[derive(Debug, Default)]
+struct Memory {
+    /// The contents of the buffer.
+    buffer: Vec<u8>,
+
+    /// The chunks waiting to be added to the buffer.
+    pending: collections::VecDeque<Vec<u8>>,
+
+    /// The position of the next read.
+    read: u64,
+
+    /// The position of the next write.
+    write: u64,
+}
+
+impl From<Vec<u8>> for Memory {
+    fn from(buffer: Vec<u8>) -> Self {
+        Self {
+            write: buffer.len() as u64,
+            buffer,
+            pending: collections::VecDeque::new(),
+            read: 0,
+        }
+    }
+}
+
+impl Memory {
+    /// Adds the next queued chunk that is not empty to the end of the buffer.
+    ///
+    /// If the write position was at the end of the buffer, it is moved to the new end so that
+    /// anything written afterwards follows the chunk. Empty chunks are skipped, since adding one
+    /// would make it look like the end of the buffer has been reached.
+    fn release(&mut self) {
+        while let Some(chunk) = self.pending.pop_front() {
+            if chunk.is_empty() {
+                continue;
+            }
+
+            let end = self.buffer.len() as u64;
+
+            self.buffer.extend_from_slice(&chunk);
+
+            if self.write == end {
+                self.write = self.buffer.len() as u64;
+            }
+
+            break;
+        }
+    }
+}
+
+impl io::Read for Memory {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !buf.is_empty() && self.read >= self.buffer.len() as u64 {
+            self.release();
+        }
+
+        let mut cursor = io::Cursor::new(&self.buffer);
+
+        cursor.set_position(self.read);
+
+        let read = cursor.read(buf)?;
+
+        self.read = cursor.position();
+
+        Ok(read)
+    }
+}
+
+impl io::Seek for Memory {
+    fn seek(&mut self, position: io::SeekFrom) -> io::Result<u64> {
+        let mut cursor = io::Cursor::new(&self.buffer);
+
+        cursor.set_position(self.read);
+
+        let position = cursor.seek(position)?;
+
+        self.read = position;
+        self.write = position;
+
+        Ok(position)
+    }
+}
+
+impl io::Write for Memory {
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let mut cursor = io::Cursor::new(&mut self.buffer);
+
+        cursor.set_position(self.write);
+
+        let written = cursor.write(buffer)?;
+
+        self.write = cursor.position();
+
+        Ok(written)
+    }
 }
 
 /// The backing streams that are supported.
 #[derive(Debug)]
 enum StreamKind {
+    /// Uses a temporary file that replaces another file when committed.
+    Atomic(atomic::Atomic),
+
+    /// Uses a custom backend provided by the application.
+    Custom(Custom),
+
+    /// Uses another stream to write compressed data to.
+    #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+    Encoder(Box<compress::Encoder>),
+
+    /// Uses another stream that fails according to a script.
+    Faulty(Box<Faulty>),
+
+    /// Uses [`fs::File`] for reading, writing, and seeking.
+    File(fs::File),
+
+    /// Uses another stream that is shared between threads.
+    Locked(std::sync::Arc<std::sync::Mutex<Stream>>),
+
     /// Uses an in-memory buffer for reading and writing.
-    Memory(io::Cursor<Vec<u8>>),
+    Memory(Memory),
+
+    /// Discards everything written and has nothing to read.
+    Null,
 
-    /// Uses [`io::Stderr`] for writing.
-    Stderr(io::Stderr),
+    /// Uses a pager that shows everything written on a terminal.
+    Pager(Box<pager::Pager>),
+
+    /// Uses [`io::Stderr`] for buffered writing.
+    Stderr(stdio::Standard),
 
     /// Uses [`io::Stdin`] for reading.
     Stdin(io::Stdin),
 
-    /// Uses [`io::Stdout`] for writing.
-    Stdout(io::Stdout),
+    /// Uses [`io::Stdout`] for buffered writing.
+    Stdout(stdio::Standard),
+
+    /// Uses a collection of streams for writing.
+    Tee(Tee),
 }
 
 /// A stream replacement that supports reading and writing.
@@ -175,7 +584,7 @@ enum StreamKind {
 ///
 /// ```
 /// use carli::io::Stream;
-/// use std::io::{self, Read, Seek, SeekFrom, Write};
+/// use std::io::Write;
 ///
 /// # fn main() {
 /// // Start with some data in the buffer.
@@ -187,62 +596,128 @@ enum StreamKind {
 /// println!("{}", content);
 ///
 /// // Write to the buffer.
-/// stream.seek(SeekFrom::Start(0)).unwrap();
-///
 /// writeln!(stream, "Hello, world!").unwrap();
 ///
-/// // And read it again.
-/// stream.seek(SeekFrom::Start(0)).unwrap();
-///
+/// // And read what was just written, since reading and writing have separate positions.
 /// let content = stream.to_string().unwrap();
 ///
-/// println!("{}", content);
+/// assert_eq!(content, "Hello, world!\n");
+///
+/// // Or check everything in the buffer without reading it.
+/// assert_eq!(stream.contents(), Some(&b"exampleHello, world!\n"[..]));
+/// # }
+/// ```
+///
+/// ### Using a file
+///
+/// ```no_run
+/// use carli::io::Stream;
+/// use std::io::Write;
+///
+/// # fn main() {
+/// // Write to the file.
+/// let mut output = Stream::create("report.txt").unwrap();
+///
+/// writeln!(output, "Hello, world!").unwrap();
+///
+/// // And read it back.
+/// let mut input = Stream::open("report.txt").unwrap();
+///
+/// println!("{}", input.to_string().unwrap());
 /// # }
 /// ```
 #[derive(Debug)]
 pub struct Stream {
+    /// The flag used to indicate that writing failed because of a broken pipe.
+    broken: bool,
+
+    /// The choice for when styles are applied.
+    color: style::ColorChoice,
+
     /// The backing stream.
     inner: StreamKind,
+
+    /// The maximum number of bytes read into memory at once.
+    limit: Option<usize>,
+
+    /// The policy for handling a broken pipe.
+    pipe: BrokenPipe,
+
+    /// The flag used to override terminal detection.
+    terminal: Option<bool>,
+}
+
+impl From<Box<dyn io::Read + Send>> for Stream {
+    fn from(reader: Box<dyn io::Read + Send>) -> Self {
+        Self::new(StreamKind::Custom(Custom::Read(reader)))
+    }
+}
+
+impl From<Box<dyn ReadWriteSeek>> for Stream {
+    fn from(backend: Box<dyn ReadWriteSeek>) -> Self {
+        Self::new(StreamKind::Custom(Custom::ReadWriteSeek(backend)))
+    }
+}
+
+impl From<Box<dyn io::Write + Send>> for Stream {
+    fn from(writer: Box<dyn io::Write + Send>) -> Self {
+        Self::new(StreamKind::Custom(Custom::Write(writer)))
+    }
+}
+
+impl From<fs::File> for Stream {
+    fn from(file: fs::File) -> Self {
+        Self::new(StreamKind::File(file))
+    }
+}
+
+impl From<io::Sink> for Stream {
+    fn from(_: io::Sink) -> Self {
+        Self::new(StreamKind::Null)
+    }
 }
 
 impl From<io::Stderr> for Stream {
-    fn from(stderr: io::Stderr) -> Self {
-        Self {
-            inner: StreamKind::Stderr(stderr),
-        }
+    fn from(_: io::Stderr) -> Self {
+        Self::new(StreamKind::Stderr(stdio::Standard::new(
+            stdio::Target::Stderr,
+        )))
     }
 }
 
 impl From<io::Stdin> for Stream {
     fn from(stdin: io::Stdin) -> Self {
-        Self {
-            inner: StreamKind::Stdin(stdin),
-        }
+        Self::new(StreamKind::Stdin(stdin))
     }
 }
 
 impl From<io::Stdout> for Stream {
-    fn from(stdout: io::Stdout) -> Self {
-        Self {
-            inner: StreamKind::Stdout(stdout),
-        }
+    fn from(_: io::Stdout) -> Self {
+        Self::new(StreamKind::Stdout(stdio::Standard::new(
+            stdio::Target::Stdout,
+        )))
     }
 }
 
 impl From<Vec<u8>> for Stream {
     fn from(buffer: Vec<u8>) -> Self {
-        Self {
-            inner: StreamKind::Memory(io::Cursor::new(buffer)),
-        }
+        Self::new(StreamKind::Memory(buffer.into()))
     }
 }
 
 impl io::Read for Stream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match &mut self.inner {
+            StreamKind::Atomic(stream) => stream.read(buf),
+            StreamKind::Custom(Custom::Read(stream)) => stream.read(buf),
+            StreamKind::Custom(Custom::ReadWriteSeek(stream)) => stream.read(buf),
+            StreamKind::Faulty(stream) => stream.read(buf),
+            StreamKind::File(stream) => stream.read(buf),
+            StreamKind::Locked(stream) => locked::lock(stream).read(buf),
             StreamKind::Memory(stream) => stream.read(buf),
+            StreamKind::Null => Ok(0),
             StreamKind::Stdin(stream) => stream.read(buf),
-            _ => unimplemented!("The stream does not support reading."),
+            _ => Err(unsupported("reading")),
         }
     }
 }
@@ -250,225 +725,2555 @@ impl io::Read for Stream {
 impl io::Seek for Stream {
     fn seek(&mut self, position: io::SeekFrom) -> io::Result<u64> {
         match &mut self.inner {
+            StreamKind::Atomic(stream) => stream.seek(position),
+            StreamKind::Custom(Custom::ReadWriteSeek(stream)) => stream.seek(position),
+            StreamKind::Faulty(stream) => stream.seek(position),
+            StreamKind::File(stream) => stream.seek(position),
+            StreamKind::Locked(stream) => locked::lock(stream).seek(position),
             StreamKind::Memory(stream) => stream.seek(position),
-            _ => unimplemented!("The stream does not support seeking."),
+            StreamKind::Null => Ok(0),
+            _ => Err(unsupported("seeking")),
         }
     }
 }
 
 impl io::Write for Stream {
     fn flush(&mut self) -> io::Result<()> {
-        match &mut self.inner {
+        let result = match &mut self.inner {
+            StreamKind::Atomic(stream) => stream.flush(),
+            StreamKind::Custom(Custom::ReadWriteSeek(stream)) => stream.flush(),
+            StreamKind::Custom(Custom::Write(stream)) => stream.flush(),
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(stream) => stream.flush(),
+            StreamKind::Faulty(stream) => stream.flush(),
+            StreamKind::File(stream) => stream.flush(),
+            StreamKind::Locked(stream) => locked::lock(stream).flush(),
             StreamKind::Memory(stream) => stream.flush(),
+            StreamKind::Null => Ok(()),
+            StreamKind::Pager(stream) => stream.flush(),
             StreamKind::Stderr(stream) => stream.flush(),
             StreamKind::Stdout(stream) => stream.flush(),
-            _ => unimplemented!("The stream does not support flushing."),
-        }
+            StreamKind::Tee(tee) => tee.apply(|sink| sink.flush()),
+            _ => Err(unsupported("flushing")),
+        };
+
+        self.check_pipe(result)
     }
 
     fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
-        match &mut self.inner {
+        let result = match &mut self.inner {
+            StreamKind::Atomic(stream) => stream.write(buffer),
+            StreamKind::Custom(Custom::ReadWriteSeek(stream)) => stream.write(buffer),
+            StreamKind::Custom(Custom::Write(stream)) => stream.write(buffer),
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(stream) => stream.write(buffer),
+            StreamKind::Faulty(stream) => stream.write(buffer),
+            StreamKind::File(stream) => stream.write(buffer),
+            StreamKind::Locked(stream) => locked::lock(stream).write(buffer),
             StreamKind::Memory(stream) => stream.write(buffer),
+            StreamKind::Null => Ok(buffer.len()),
+            StreamKind::Pager(stream) => stream.write(buffer),
             StreamKind::Stderr(stream) => stream.write(buffer),
             StreamKind::Stdout(stream) => stream.write(buffer),
-            _ => unimplemented!("The stream does not support writing."),
-        }
+            StreamKind::Tee(tee) => tee
+                .apply(|sink| sink.write_all(buffer))
+                .map(|_| buffer.len()),
+            _ => Err(unsupported("writing")),
+        };
+
+        self.check_pipe(result)
     }
 }
 
 impl Stream {
-    /// Reads the stream into a string.
+    /// Opens a file for appending and uses it as the stream.
     ///
-    /// This method will read from the current position in the stream all the way to the end. The
-    /// contents that have been read will then be parsed as a [`String`] and the result is returned
-    /// as is.
+    /// The file is created if it does not already exist. Anything written to the stream will be
+    /// added to the end of the file, leaving the existing contents intact.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::Write;
     ///
+    /// # fn main() {
+    /// let mut stream = Stream::append("example.log").unwrap();
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    /// # }
     /// ```
+    pub fn append<P: AsRef<path::Path>>(path: P) -> Result<Self, io::Error> {
+        let file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        Ok(file.into())
+    }
+
+    /// Creates a stream that replaces a file only once it has been committed.
+    ///
+    /// Everything is written to a temporary file in the same directory as the target file, which
+    /// is left untouched until [`Stream::commit`] renames the temporary file over it. If the target
+    /// already exists, its permissions are copied to the temporary file. If the stream is dropped
+    /// without being committed, such as when a command returns an error, the temporary file is
+    /// removed instead (see [`discard_uncommitted`] for exiting the process early).
+    ///
+    /// ```no_run
     /// use carli::error::Result;
     /// use carli::io::Stream;
+    /// use std::io::Write;
     ///
-    /// fn example(stream: &mut Stream) -> Result<()> {
-    ///     let string = stream.to_string()?;
+    /// fn example() -> Result<()> {
+    ///     let mut stream = Stream::atomic("example.txt")?;
     ///
-    ///     println!("{}", string);
+    ///     writeln!(stream, "Hello, world!")?;
+    ///
+    ///     stream.commit()?;
     ///
     ///     Ok(())
     /// }
     /// ```
-    pub fn to_string(&mut self) -> Result<String, io::Error> {
-        String::from_utf8(self.as_buffer()?)
-            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    pub fn atomic<P: AsRef<path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(Self::new(StreamKind::Atomic(atomic::Atomic::new(
+            path.as_ref(),
+        )?)))
     }
 
-    /// Reads the stream into a lossy string.
+    /// Attaches another stream that will receive a copy of everything written.
     ///
-    /// This method will read from the current position in the stream all the way to the end. The
-    /// contents that have been read willl then be parsed as a lossy [`String`] and the result is
-    /// returned as is.
+    /// If the stream is not already a tee (see [`Stream::tee`]), it will become one with itself as
+    /// the first stream and [`TeePolicy::FailFast`] as the policy. The attached stream is added to
+    /// the end of the tee, and can be accessed using [`Stream::sinks_mut`].
     ///
     /// ```
     /// use carli::io::Stream;
+    /// use std::io::{self, Write};
     ///
-    /// fn example(stream: &mut Stream) {
-    ///     let string = stream.to_string_lossy();
+    /// # fn main() {
+    /// let mut stream: Stream = io::stdout().into();
     ///
-    ///     println!("{}", string);
-    /// }
+    /// stream.attach(Vec::new());
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    ///
+    /// let copy = &stream.sinks_mut()[1];
+    ///
+    /// assert_eq!(copy.contents(), Some(&b"Hello, world!\n"[..]));
+    /// # }
     /// ```
-    pub fn to_string_lossy(&mut self) -> String {
-        let buffer = self
-            .as_buffer()
-            .expect("Could not read the stream into the buffer.");
-
-        String::from_utf8_lossy(&buffer).to_string()
+    pub fn attach<S: Into<Stream>>(&mut self, sink: S) {
+        if let StreamKind::Tee(tee) = &mut self.inner {
+            tee.sinks.push(sink.into());
+        } else {
+            let primary = std::mem::replace(self, Vec::new().into());
+
+            self.color = primary.color;
+            self.limit = primary.limit;
+            self.pipe = primary.pipe;
+            self.terminal = primary.terminal;
+            self.inner = StreamKind::Tee(Tee {
+                policy: TeePolicy::default(),
+                sinks: vec![primary, sink.into()],
+            });
+        }
     }
 
-    /// Reads the contents of the stream into a buffer.
+    /// Returns the operations supported by the stream.
     ///
-    /// This method will read the stream all the way to the end and store the contents in a
-    /// buffer that is then returned. If this stream is [`StreamKind::Memory`], the the buffer
-    /// position will be reset to the beginning before reading.
-    fn as_buffer(&mut self) -> Result<Vec<u8>, io::Error> {
-        use std::io::Read;
-
-        let mut buffer = Vec::new();
+    /// The capabilities describe the kind of stream rather than how it was opened. A file that was
+    /// opened only for reading, for example, is still reported as writable since the operating
+    /// system will be the one to reject the write.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let stream: Stream = Vec::new().into();
+    /// let capabilities = stream.capabilities();
+    ///
+    /// assert!(capabilities.is_readable());
+    /// assert!(capabilities.is_seekable());
+    /// assert!(capabilities.is_writable());
+    /// # }
+    /// ```
+    pub fn capabilities(&self) -> Capabilities {
+        let (readable, seekable, writable) = match &self.inner {
+            StreamKind::Atomic(_) => (true, true, true),
+            StreamKind::Custom(Custom::Read(_)) => (true, false, false),
+            StreamKind::Custom(Custom::ReadWriteSeek(_)) => (true, true, true),
+            StreamKind::Custom(Custom::Write(_)) => (false, false, true),
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(_) => (false, false, true),
+            StreamKind::Faulty(stream) => return stream.inner().capabilities(),
+            StreamKind::File(_) => (true, true, true),
+            StreamKind::Locked(stream) => return locked::lock(stream).capabilities(),
+            StreamKind::Memory(_) => (true, true, true),
+            StreamKind::Null => (true, true, true),
+            StreamKind::Pager(_) => (false, false, true),
+            StreamKind::Stderr(_) => (false, false, true),
+            StreamKind::Stdin(_) => (true, false, false),
+            StreamKind::Stdout(_) => (false, false, true),
+            StreamKind::Tee(_) => (false, false, true),
+        };
 
-        self.read_to_end(&mut buffer)?;
+        Capabilities {
+            readable,
+            seekable,
+            writable,
+        }
+    }
+
+    /// Returns an iterator over fixed-size chunks of the stream.
+    ///
+    /// Unlike [`Stream::to_string`], the stream is read a little at a time which makes it possible
+    /// to process input that is too large to fit into memory. Every chunk will be the given size,
+    /// except for the last chunk which may be shorter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size is zero.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) -> Result<()> {
+    ///     for chunk in stream.chunks(4096) {
+    ///         println!("Read {} bytes.", chunk?.len());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn chunks(&mut self, size: usize) -> Chunks<'_> {
+        Chunks::new(self, size)
+    }
+
+    /// Returns the choice for when styles are applied to the stream.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use carli::style::ColorChoice;
+    ///
+    /// # fn main() {
+    /// let stream: Stream = Vec::new().into();
+    ///
+    /// assert_eq!(stream.color(), ColorChoice::Auto);
+    /// # }
+    /// ```
+    pub fn color(&self) -> style::ColorChoice {
+        self.color
+    }
+
+    /// Commits everything written to the stream.
+    ///
+    /// For a stream created with [`Stream::atomic`], the temporary file is renamed over the target
+    /// file, and the stream continues to use the target file. For a stream compressed with
+    /// [`Stream::compress`], the end of the compressed data is written before the stream it was
+    /// written to is committed, and the stream continues to use what it was before without
+    /// compressing it. For a stream shown by a pager (see [`Stream::page`]), the pager is
+    /// waited on, and the stream continues to use what it was before. The streams of a tee are
+    /// committed in order. Any other stream is simply flushed.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::atomic("example.txt").unwrap();
+    ///
+    /// write!(stream, "Hello, world!").unwrap();
+    ///
+    /// stream.commit().unwrap();
+    /// # }
+    /// ```
+    pub fn commit(&mut self) -> Result<(), io::Error> {
+        use std::io::Write;
+
+        match &mut self.inner {
+            StreamKind::Atomic(atomic) => {
+                self.inner = StreamKind::File(atomic.commit()?);
+
+                Ok(())
+            }
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(encoder) => {
+                encoder.finish()?;
+
+                let mut inner = std::mem::replace(encoder.inner_mut(), Stream::null());
+                let result = inner.commit();
+
+                self.inner = inner.inner;
+
+                result
+            }
+            StreamKind::Locked(stream) => locked::lock(stream).commit(),
+            StreamKind::Pager(pager) => {
+                let result = pager.close();
+
+                if let StreamKind::Pager(pager) =
+                    std::mem::replace(&mut self.inner, StreamKind::Null)
+                {
+                    self.inner = pager.into_target().inner;
+                }
+
+                result
+            }
+            StreamKind::Tee(tee) => tee.sinks.iter_mut().try_for_each(Stream::commit),
+            _ => self.flush(),
+        }
+    }
+
+    /// Compresses everything that is written to the stream from now on.
+    ///
+    /// The compressed data is written to what the stream was before, which means that it can be
+    /// combined with other kinds of streams, such as those created by [`Stream::atomic`]. The end
+    /// of the compressed data is written when the stream is committed (see [`Stream::commit`]), or
+    /// when it is dropped. Each format requires its cargo feature to be enabled.
+    ///
+    /// ```no_run
+    /// use carli::error::Result;
+    /// use carli::io::{self, Compression};
+    /// use std::io::Write;
+    ///
+    /// fn example(path: &str, compression: Option<Compression>) -> Result<()> {
+    ///     let mut output = io::open_output(path)?;
+    ///
+    ///     if let Some(compression) = compression.or_else(|| Compression::from_path(path)) {
+    ///         output.compress(compression)?;
+    ///     }
+    ///
+    ///     writeln!(output, "Hello, world!")?;
+    ///
+    ///     output.commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+    pub fn compress(&mut self, compression: Compression) -> Result<(), io::Error> {
+        let inner = std::mem::replace(self, Stream::null());
+
+        self.color = inner.color;
+        self.limit = inner.limit;
+        self.pipe = inner.pipe;
+        self.terminal = inner.terminal;
+        self.inner = StreamKind::Encoder(Box::new(compress::Encoder::new(inner, compression)?));
+
+        Ok(())
+    }
+
+    /// Returns the contents of an in-memory buffer without reading from it.
+    ///
+    /// Unlike [`Stream::to_string`], the read position of the buffer is not used or changed, which
+    /// makes it possible to check everything that has been written regardless of what has already
+    /// been read. For a stream compressed with [`Stream::compress`], the compressed contents of the
+    /// buffer it writes to are returned. If the stream is not an in-memory buffer, [`None`] is
+    /// returned.
+    ///
+    /// Once the stream has been shared between threads using [`Streams::sync`], its contents can
+    /// no longer be borrowed from it, so [`None`] is also returned. The contents are still
+    /// available by locking the shared stream using [`SyncShared`], or as a string using
+    /// [`Streams::error_string`] and [`Streams::output_string`].
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Vec::new().into();
+    ///
+    /// write!(stream, "Hello, world!").unwrap();
+    ///
+    /// assert_eq!(stream.contents(), Some(&b"Hello, world!"[..]));
+    /// # }
+    /// ```
+    pub fn contents(&self) -> Option<&[u8]> {
+        match &self.inner {
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(stream) => stream.inner().contents(),
+            StreamKind::Faulty(stream) => stream.inner().contents(),
+            StreamKind::Locked(_) => None,
+            StreamKind::Memory(memory) => Some(&memory.buffer),
+            _ => None,
+        }
+    }
+
+    /// Creates a stream using a custom backend that supports reading, writing, and seeking.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::{Cursor, Seek, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::custom(Cursor::new(Vec::new()));
+    ///
+    /// write!(stream, "Hello, world!").unwrap();
+    ///
+    /// stream.rewind().unwrap();
+    ///
+    /// assert_eq!(stream.to_string().unwrap(), "Hello, world!");
+    /// # }
+    /// ```
+    pub fn custom<T: ReadWriteSeek + 'static>(backend: T) -> Self {
+        let backend: Box<dyn ReadWriteSeek> = Box::new(backend);
+
+        backend.into()
+    }
+
+    /// Creates a file for writing and uses it as the stream.
+    ///
+    /// The file is created if it does not already exist, and truncated if it does.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::create("report.txt").unwrap();
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    /// # }
+    /// ```
+    pub fn create<P: AsRef<path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(fs::File::create(path)?.into())
+    }
+
+    /// Creates a new file for writing and uses it as the stream.
+    ///
+    /// Unlike [`Stream::create`], this method will fail if the file already exists. This may be
+    /// used to avoid accidentally overwriting a file that the user did not intend to replace.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::create_new("report.txt").unwrap();
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    /// # }
+    /// ```
+    pub fn create_new<P: AsRef<path::Path>>(path: P) -> Result<Self, io::Error> {
+        let file = fs::OpenOptions::new()
+            .create_new(true)
+            .read(true)
+            .write(true)
+            .open(path)?;
+
+        Ok(file.into())
+    }
+
+    /// Decompresses the rest of the stream as it is read, if it is compressed.
+    ///
+    /// The first few bytes of the stream are read to check for the magic bytes of a supported
+    /// compression format. If one is found, the stream is replaced in place with one that reads
+    /// the decompressed data, which means that [`Stream::to_string`], [`Stream::lines`], and
+    /// [`Shared::input`] all keep working as usual. Otherwise, the stream is left as it was, apart
+    /// from having to be read from the start of the bytes that were checked. Each format requires
+    /// its cargo feature to be enabled.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Shared;
+    ///
+    /// fn example(context: &dyn Shared) -> Result<()> {
+    ///     context.input().decompress()?;
+    ///
+    ///     for line in context.input().lines() {
+    ///         println!("{}", line?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+    pub fn decompress(&mut self) -> Result<(), io::Error> {
+        use std::io::Read;
+
+        let mut magic = Vec::new();
+
+        (&mut *self).take(6).read_to_end(&mut magic)?;
+
+        let compression = compress::Compression::detect(&magic);
+
+        if compression.is_none() && self.capabilities().is_seekable() {
+            return self.unread(magic.len());
+        }
+
+        let inner = std::mem::replace(self, Stream::null());
+        let (color, limit, pipe, terminal) = (inner.color, inner.limit, inner.pipe, inner.terminal);
+        let reader = io::Cursor::new(magic).chain(inner);
+
+        *self = match compression {
+            Some(compression) => compress::decoder(reader, compression)?.into(),
+            None => Stream::reader(reader),
+        };
+
+        self.color = color;
+        self.limit = limit;
+        self.pipe = pipe;
+        self.terminal = terminal;
+
+        Ok(())
+    }
+
+    /// Checks if writing to the stream has failed because of a broken pipe.
+    ///
+    /// Once the process reading the other end of a pipe has gone away, nothing written will ever
+    /// be read. A command that spends a lot of time producing its output can check this to stop
+    /// early, without waiting for its next write to fail.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Shared;
+    /// use std::io::Write;
+    ///
+    /// fn example(context: &dyn Shared) -> Result<()> {
+    ///     for i in 0.. {
+    ///         if context.output().is_broken_pipe() {
+    ///             break;
+    ///         }
+    ///
+    ///         let _ = writeln!(context.output(), "Line {}", i);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn is_broken_pipe(&self) -> bool {
+        self.broken
+    }
+
+    /// Checks if the stream is connected to a terminal.
+    ///
+    /// This may be used to decide between output intended for people and output intended for other
+    /// programs, or to disable interactive features when the input is piped. Streams that are not
+    /// backed by a standard stream or file, such as in-memory buffers, are never terminals unless
+    /// [`Stream::set_terminal`] has been used to say otherwise.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut output: Stream = io::stdout().into();
+    ///
+    /// if output.is_terminal() {
+    ///     writeln!(output, "Hello, world!").unwrap();
+    /// } else {
+    ///     writeln!(output, "{{\"message\":\"Hello, world!\"}}").unwrap();
+    /// }
+    /// # }
+    /// ```
+    pub fn is_terminal(&self) -> bool {
+        use std::io::IsTerminal;
+
+        if let Some(terminal) = self.terminal {
+            return terminal;
+        }
+
+        match &self.inner {
+            StreamKind::Faulty(stream) => stream.inner().is_terminal(),
+            StreamKind::File(stream) => stream.is_terminal(),
+            StreamKind::Locked(stream) => locked::lock(stream).is_terminal(),
+            StreamKind::Pager(pager) => pager.target().is_terminal(),
+            StreamKind::Stderr(stream) => stream.is_terminal(),
+            StreamKind::Stdin(stream) => stream.is_terminal(),
+            StreamKind::Stdout(stream) => stream.is_terminal(),
+            StreamKind::Tee(tee) => tee.sinks.first().is_some_and(Stream::is_terminal),
+            _ => false,
+        }
+    }
+
+    /// Returns an iterator over the lines of the stream.
+    ///
+    /// Unlike [`Stream::to_string`], the stream is read a little at a time which makes it possible
+    /// to process input that is too large to fit into memory. If a line is not valid UTF-8, the
+    /// error returned will say which line it was.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) -> Result<()> {
+    ///     for line in stream.lines() {
+    ///         println!("{}", line?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn lines(&mut self) -> Lines<'_> {
+        Lines::new(self)
+    }
+
+    /// Creates a stream that discards everything written to it.
+    ///
+    /// The stream behaves like `/dev/null`: writes always succeed, reads always reach the end of
+    /// the stream, and nothing is ever allocated. This is useful for silencing output, such as
+    /// when the user has requested that an application be quiet.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::null();
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    ///
+    /// assert_eq!(stream.to_string().unwrap(), "");
+    /// # }
+    /// ```
+    pub fn null() -> Self {
+        Self::new(StreamKind::Null)
+    }
+
+    /// Opens a file for reading and uses it as the stream.
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::open("report.txt").unwrap();
+    ///
+    /// println!("{}", stream.to_string().unwrap());
+    /// # }
+    /// ```
+    pub fn open<P: AsRef<path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(fs::File::open(path)?.into())
+    }
+
+    /// Shows everything that is written to the stream from now on using a pager.
+    ///
+    /// The pager is the command in the `PAGER` environment variable, or `less -FRX` if it is not
+    /// set. Nothing is done if the stream is not connected to a terminal, if `PAGER` is empty or
+    /// `cat`, or if the pager cannot be found, which means that output that is piped to another
+    /// program is never paged. Whether a pager was started is returned.
+    ///
+    /// The pager must be waited on before the application exits, which is done when the stream is
+    /// committed (see [`Stream::commit`]) or dropped, or when [`crate::error::Error::exit`] is
+    /// used. If the pager is quit before everything has been written, writing fails because of a
+    /// broken pipe (see [`Stream::set_broken_pipe`]).
+    ///
+    /// ```no_run
+    /// use carli::error::Result;
+    /// use carli::io::Shared;
+    /// use std::io::Write;
+    ///
+    /// fn example(context: &dyn Shared, records: &[String], no_pager: bool) -> Result<()> {
+    ///     if !no_pager {
+    ///         context.output().page()?;
+    ///     }
+    ///
+    ///     for record in records {
+    ///         writeln!(context.output(), "{}", record)?;
+    ///     }
+    ///
+    ///     context.output().commit()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn page(&mut self) -> Result<bool, io::Error> {
+        match pager::from_env(std::env::var_os("PAGER").as_deref()) {
+            Some(command) => self.page_using(command),
+            None => Ok(false),
+        }
+    }
+
+    /// Shows everything that is written to the stream from now on using the given pager.
+    ///
+    /// The command is run using the shell, and is used instead of the `PAGER` environment
+    /// variable, such as when the pager is chosen in the configuration of the application.
+    /// Otherwise, the stream behaves the same way as it does for [`Stream::page`].
+    ///
+    /// ```no_run
+    /// use carli::io::Stream;
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut output: Stream = io::stdout().into();
+    ///
+    /// output.page_with("more").unwrap();
+    ///
+    /// writeln!(output, "Hello, world!").unwrap();
+    ///
+    /// output.commit().unwrap();
+    /// # }
+    /// ```
+    pub fn page_with(&mut self, command: &str) -> Result<bool, io::Error> {
+        match pager::shell(command.as_ref()) {
+            Some(command) => self.page_using(command),
+            None => Ok(false),
+        }
+    }
+
+    /// Creates an in-memory buffer that receives its contents one chunk at a time.
+    ///
+    /// Only the first chunk can be read at first. Each of the following chunks is added to the
+    /// buffer once everything before it has been read, which makes it possible to simulate input
+    /// that arrives while an interactive session is in progress. Everything is available to
+    /// [`Stream::to_string`], since it keeps reading until all of the chunks have been read.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::Read;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::queued(["first\n", "second\n"]);
+    /// let mut buffer = [0; 64];
+    ///
+    /// assert_eq!(stream.read(&mut buffer).unwrap(), 6);
+    /// assert_eq!(stream.to_string().unwrap(), "second\n");
+    /// # }
+    /// ```
+    pub fn queued<I, B>(chunks: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: AsRef<[u8]>,
+    {
+        let memory = Memory {
+            pending: chunks
+                .into_iter()
+                .map(|chunk| chunk.as_ref().to_vec())
+                .collect(),
+            ..Memory::default()
+        };
+
+        Self::new(StreamKind::Memory(memory))
+    }
+
+    /// Creates a stream using a custom reader.
+    ///
+    /// The stream will only support reading. Any attempt to write to or seek the stream will
+    /// result in an error.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::Read;
+    ///
+    /// # fn main() {
+    /// let reader = b"Hello, ".chain(&b"world!"[..]);
+    /// let mut stream = Stream::reader(reader);
+    ///
+    /// assert_eq!(stream.to_string().unwrap(), "Hello, world!");
+    /// # }
+    /// ```
+    pub fn reader<R: io::Read + Send + 'static>(reader: R) -> Self {
+        let reader: Box<dyn io::Read + Send> = Box::new(reader);
+
+        reader.into()
+    }
+
+    /// Sets the policy for handling a broken pipe when writing to the stream.
+    ///
+    /// See [`BrokenPipe`] for more information.
+    ///
+    /// ```
+    /// use carli::io::{BrokenPipe, Stream};
+    /// use std::io;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = io::stdout().into();
+    ///
+    /// stream.set_broken_pipe(BrokenPipe::Quiet(0));
+    /// # }
+    /// ```
+    pub fn set_broken_pipe(&mut self, policy: BrokenPipe) {
+        self.pipe = policy;
+    }
+
+    /// Sets the strategy for buffering writes to a standard stream.
+    ///
+    /// Writes to the standard error and output streams are collected into a buffer, which is only
+    /// written to the standard stream as required by the strategy. Anything that has already been
+    /// buffered is written before the strategy is changed. See [`Buffering`] for the strategies
+    /// that are used by default. Other kinds of streams are not affected.
+    ///
+    /// ```
+    /// use carli::io::{Buffering, Stream};
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = io::stdout().into();
+    ///
+    /// stream.set_buffering(Buffering::Block).unwrap();
+    ///
+    /// for i in 0..1000 {
+    ///     writeln!(stream, "Line {}", i).unwrap();
+    /// }
+    ///
+    /// stream.flush().unwrap();
+    /// # }
+    /// ```
+    pub fn set_buffering(&mut self, buffering: Buffering) -> Result<(), io::Error> {
+        match &mut self.inner {
+            #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+            StreamKind::Encoder(stream) => stream.inner_mut().set_buffering(buffering),
+            StreamKind::Faulty(stream) => stream.inner_mut().set_buffering(buffering),
+            StreamKind::Locked(stream) => locked::lock(stream).set_buffering(buffering),
+            StreamKind::Stderr(stream) => stream.set_buffering(buffering),
+            StreamKind::Stdout(stream) => stream.set_buffering(buffering),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the choice for when styles are applied to the stream.
+    ///
+    /// See [`crate::style`] for more information on how styles are applied.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use carli::style::ColorChoice;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Vec::new().into();
+    ///
+    /// stream.set_color(ColorChoice::Never);
+    /// # }
+    /// ```
+    pub fn set_color(&mut self, color: style::ColorChoice) {
+        self.color = color;
+    }
+
+    /// Sets the limit on how many bytes [`Stream::to_string`] and [`Stream::to_text`] may read.
+    ///
+    /// Both methods otherwise read everything that is left in the stream into memory. If there is
+    /// more to read than the limit allows, an error is returned instead of a truncated result. See
+    /// [`Stream::to_string_limited`] for more information. The limit is not applied to anything
+    /// else: [`Stream::to_string_lossy`] ignores it and reads the whole stream, however large it
+    /// is, and so does reading the stream directly using [`std::io::Read`].
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = b"Hello, world!".to_vec().into();
+    ///
+    /// stream.set_read_limit(Some(5));
+    ///
+    /// assert!(stream.to_string().is_err());
+    /// # }
+    /// ```
+    pub fn set_read_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    /// Overrides terminal detection for the stream.
+    ///
+    /// This is primarily useful when testing, where an in-memory buffer can pretend to be a
+    /// terminal so that behavior which depends on [`Stream::is_terminal`] can be verified. It may
+    /// also be used to treat a standard stream as if it were not connected to a terminal.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Vec::new().into();
+    ///
+    /// stream.set_terminal(true);
+    ///
+    /// assert!(stream.is_terminal());
+    /// # }
+    /// ```
+    pub fn set_terminal(&mut self, terminal: bool) {
+        self.terminal = Some(terminal);
+    }
+
+    /// Returns the streams that are part of the tee.
+    ///
+    /// If the stream is not a tee, an empty slice is returned.
+    ///
+    /// ```
+    /// use carli::io::{Stream, TeePolicy};
+    /// use std::io::Write;
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::tee(vec![Vec::new().into()], TeePolicy::FailFast);
+    ///
+    /// write!(stream, "Hello, world!").unwrap();
+    ///
+    /// let copy = &stream.sinks_mut()[0];
+    ///
+    /// assert_eq!(copy.contents(), Some(&b"Hello, world!"[..]));
+    /// # }
+    /// ```
+    pub fn sinks_mut(&mut self) -> &mut [Stream] {
+        match &mut self.inner {
+            StreamKind::Tee(tee) => &mut tee.sinks,
+            _ => &mut [],
+        }
+    }
+
+    /// Returns an iterator over the records of the stream that are separated by a delimiter.
+    ///
+    /// This may be used to read input that is separated by something other than a newline, such as
+    /// the `NUL` separated output of `find -print0`. The records are returned as bytes since they
+    /// may not be valid UTF-8 (e.g. paths on some platforms).
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) -> Result<()> {
+    ///     for path in stream.split(b'\0') {
+    ///         println!("{}", String::from_utf8_lossy(&path?));
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn split(&mut self, delimiter: u8) -> Split<'_> {
+        Split::new(self, delimiter)
+    }
+
+    /// Converts the stream into one that can be rewound, by reading the rest of it into storage.
+    ///
+    /// Some streams, such as [`io::Stdin`], can only be read once. When a command needs to make
+    /// more than one pass over its input, the stream can be spooled first. Everything from the
+    /// current position to the end is read into memory, unless it is larger than the threshold
+    /// (in bytes), in which case it is read into an anonymous temporary file instead. The stream
+    /// is then backed by that storage, starting at the beginning of what was read, and supports
+    /// seeking. Since the conversion happens in place, [`Shared::input`] will keep returning the
+    /// spooled stream.
+    ///
+    /// Streams that already support seeking are left as they are.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Shared;
+    /// use std::io::Seek;
+    ///
+    /// fn example(context: &dyn Shared) -> Result<()> {
+    ///     context.input().spool(1024 * 1024)?;
+    ///
+    ///     let count = context.input().lines().count();
+    ///
+    ///     context.input().rewind()?;
+    ///
+    ///     for (number, line) in context.input().lines().enumerate() {
+    ///         println!("{}/{}: {}", number + 1, count, line?);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn spool(&mut self, threshold: usize) -> Result<(), io::Error> {
+        use std::io::{Read, Seek, Write};
+
+        if self.capabilities().is_seekable() {
+            return Ok(());
+        }
+
+        let mut buffer = Vec::new();
+
+        self.take((threshold as u64).saturating_add(1))
+            .read_to_end(&mut buffer)?;
+
+        if buffer.len() <= threshold {
+            self.inner = StreamKind::Memory(buffer.into());
+
+            return Ok(());
+        }
+
+        let mut file = tempfile::tempfile()?;
+
+        file.write_all(&buffer)?;
+
+        io::copy(self, &mut file)?;
+
+        file.rewind()?;
+
+        self.inner = StreamKind::File(file);
+
+        Ok(())
+    }
+
+    /// Creates a stream that writes to every one of the given streams.
+    ///
+    /// Each write and flush is repeated for every stream, in the order that they were given. The
+    /// policy determines what happens when one of the streams fails. The tee is considered to be
+    /// a terminal if its first stream is a terminal, which makes it possible to mirror output to
+    /// a log file without affecting how that output is styled.
+    ///
+    /// ```no_run
+    /// use carli::io::{Stream, Streams, TeePolicy};
+    /// use std::io;
+    ///
+    /// # fn main() {
+    /// let output = Stream::tee(
+    ///     vec![io::stdout().into(), Stream::append("output.log").unwrap()],
+    ///     TeePolicy::BestEffort,
+    /// );
+    ///
+    /// let streams = Streams::builder().output(output).build();
+    /// # }
+    /// ```
+    pub fn tee<I: IntoIterator<Item = Stream>>(sinks: I, policy: TeePolicy) -> Self {
+        Self::new(StreamKind::Tee(Tee {
+            policy,
+            sinks: sinks.into_iter().collect(),
+        }))
+    }
+
+    /// Reads the stream into a string.
+    ///
+    /// This method will read from the current position in the stream all the way to the end. The
+    /// contents that have been read will then be parsed as a [`String`] and the result is returned
+    /// as is. To read text that may not be UTF-8, use [`Stream::to_text`] instead.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) -> Result<()> {
+    ///     let string = stream.to_string()?;
+    ///
+    ///     println!("{}", string);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_string(&mut self) -> Result<String, io::Error> {
+        String::from_utf8(self.as_buffer()?)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+
+    /// Reads the stream into a string, as long as it is not larger than the limit.
+    ///
+    /// This method works like [`Stream::to_string`], except that at most one byte more than the
+    /// limit is read from the stream. If that byte can be read, the stream is too large and an
+    /// error is returned instead of a truncated result. When converted into
+    /// [`crate::error::Error`], the error uses the OS error code for a file that is too large
+    /// (`EFBIG`) as its exit status code. The limit given replaces the one set using
+    /// [`Stream::set_read_limit`].
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) -> Result<()> {
+    ///     let string = stream.to_string_limited(1024 * 1024)?;
+    ///
+    ///     println!("{}", string);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_string_limited(&mut self, limit: usize) -> Result<String, io::Error> {
+        String::from_utf8(self.read_limited(Some(limit))?)
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+
+    /// Reads the stream into a lossy string.
+    ///
+    /// This method will read from the current position in the stream all the way to the end. The
+    /// contents that have been read willl then be parsed as a lossy [`String`] and the result is
+    /// returned as is.
+    ///
+    /// The read limit set using [`Stream::set_read_limit`] is ignored, so the whole stream is read
+    /// into memory however large it is. Use [`Stream::to_string`] or [`Stream::to_string_limited`]
+    /// for input that cannot be trusted.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// fn example(stream: &mut Stream) {
+    ///     let string = stream.to_string_lossy();
+    ///
+    ///     println!("{}", string);
+    /// }
+    /// ```
+    pub fn to_string_lossy(&mut self) -> String {
+        let buffer = self
+            .read_limited(None)
+            .expect("Could not read the stream into the buffer.");
+
+        String::from_utf8_lossy(&buffer).to_string()
+    }
+
+    /// Reads the stream into a string, decoding it using the options.
+    ///
+    /// This method will read from the current position in the stream all the way to the end. The
+    /// encoding is detected using the byte order mark at the start of what was read, unless one
+    /// is set using [`Text::encoding`], and UTF-8 is assumed if there is none. If the contents
+    /// cannot be decoded, the error includes the byte offset where decoding failed.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::{Encoding, Stream, Text};
+    ///
+    /// fn example(stream: &mut Stream, encoding: Option<Encoding>) -> Result<()> {
+    ///     let mut text = Text::new().normalize_newlines(true);
+    ///
+    ///     if let Some(encoding) = encoding {
+    ///         text = text.encoding(encoding);
+    ///     }
+    ///
+    ///     println!("{}", stream.to_text(text)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn to_text(&mut self, text: Text) -> error::Result<String> {
+        let buffer = self
+            .as_buffer()
+            .map_err(|error| Error::from(error).context("Could not read the stream."))?;
+
+        text.decode(&buffer)
+    }
+
+    /// Creates a stream using a custom writer.
+    ///
+    /// The stream will only support writing. Any attempt to read from or seek the stream will
+    /// result in an error. The writer could be anything from a network socket to a wrapper that
+    /// counts the number of bytes written.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use std::io::{BufWriter, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream = Stream::writer(BufWriter::new(std::io::sink()));
+    ///
+    /// writeln!(stream, "Hello, world!").unwrap();
+    /// # }
+    /// ```
+    pub fn writer<W: io::Write + Send + 'static>(writer: W) -> Self {
+        let writer: Box<dyn io::Write + Send> = Box::new(writer);
+
+        writer.into()
+    }
+
+    /// Creates a new instance using the given backing stream.
+    fn new(inner: StreamKind) -> Self {
+        Self {
+            broken: false,
+            color: style::ColorChoice::default(),
+            inner,
+            limit: None,
+            pipe: BrokenPipe::default(),
+            terminal: None,
+        }
+    }
+
+    /// Reads the contents of the stream into a buffer.
+    ///
+    /// This method will read the stream all the way to the end and store the contents in a
+    /// buffer that is then returned. If this stream is [`StreamKind::Memory`], reading starts
+    /// at its read position. The read limit of the stream is applied.
+    fn as_buffer(&mut self) -> Result<Vec<u8>, io::Error> {
+        self.read_limited(self.limit)
+    }
+
+    /// Reads the rest of the stream into a buffer, failing if it is larger than the limit.
+    fn read_limited(&mut self, limit: Option<usize>) -> Result<Vec<u8>, io::Error> {
+        use std::io::Read;
+
+        let mut buffer = Vec::new();
+
+        match limit {
+            Some(limit) => {
+                (&mut *self)
+                    .take((limit as u64).saturating_add(1))
+                    .read_to_end(&mut buffer)?;
+
+                if buffer.len() > limit {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        TooLarge { limit },
+                    ));
+                }
+            }
+            None => {
+                self.read_to_end(&mut buffer)?;
+            }
+        }
 
         Ok(buffer)
     }
+
+    /// Records a broken pipe in the result of a write, and applies the policy for handling it.
+    fn check_pipe<T>(&mut self, result: io::Result<T>) -> io::Result<T> {
+        match result {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {
+                self.broken = true;
+
+                match self.pipe {
+                    BrokenPipe::Quiet(status) => Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        QuietPipe {
+                            message: error.to_string(),
+                            status,
+                        },
+                    )),
+                    BrokenPipe::Report => Err(error),
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// Starts the pager using the command, if the stream is shown on a terminal.
+    fn page_using(&mut self, command: std::process::Command) -> io::Result<bool> {
+        if !self.is_terminal() {
+            return Ok(false);
+        }
+
+        let output = match &self.inner {
+            StreamKind::File(file) => std::process::Stdio::from(file.try_clone()?),
+            StreamKind::Stdout(_) => std::process::Stdio::inherit(),
+            _ => return Ok(false),
+        };
+
+        // Anything already written must be shown before what is written to the pager.
+        io::Write::flush(self)?;
+
+        let pager = match pager::Pager::start(command, output, self) {
+            Ok(pager) => pager,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(error) => return Err(error),
+        };
+
+        let target = pager.target();
+
+        self.color = target.color;
+        self.limit = target.limit;
+        self.pipe = target.pipe;
+        self.terminal = target.terminal;
+        self.inner = StreamKind::Pager(Box::new(pager));
+
+        Ok(true)
+    }
+
+    /// Returns the file descriptor of the stream, if it is backed by one.
+    #[cfg(unix)]
+    pub(crate) fn raw_fd(&self) -> Option<std::os::unix::io::RawFd> {
+        use std::os::unix::io::AsRawFd;
+
+        match &self.inner {
+            StreamKind::Faulty(stream) => stream.inner().raw_fd(),
+            StreamKind::File(file) => Some(file.as_raw_fd()),
+            StreamKind::Locked(stream) => locked::lock(stream).raw_fd(),
+            StreamKind::Stdin(stdin) => Some(stdin.as_raw_fd()),
+            _ => None,
+        }
+    }
+
+    /// Moves the read position of a seekable stream back by the number of bytes.
+    ///
+    /// Only the read position of an in-memory buffer is moved, so that writing continues from
+    /// where it left off instead of overwriting what was read.
+    #[cfg(any(feature = "gzip", feature = "xz", feature = "zstd"))]
+    fn unread(&mut self, count: usize) -> io::Result<()> {
+        use std::io::Seek;
+
+        match &mut self.inner {
+            StreamKind::Locked(stream) => locked::lock(stream).unread(count),
+            StreamKind::Memory(memory) => {
+                memory.read = memory.read.saturating_sub(count as u64);
+
+                Ok(())
+            }
+            _ => self
+                .seek(io::SeekFrom::Current(-(count as i64)))
+                .map(|_| ()),
+        }
+    }
+}
+
+/// The error used when an input file opened by [`open_inputs`] could not be opened or read.
+///
+/// When converted into [`crate::error::Error`], the path is added to the context, and the message
+/// and exit status code are those of the original error.
+#[derive(Debug)]
+struct InputFile {
+    /// What was being done with the file.
+    action: &'static str,
+
+    /// The original error.
+    error: io::Error,
+
+    /// The path of the file.
+    path: path::PathBuf,
+}
+
+impl InputFile {
+    /// Creates an error that includes the path of the file.
+    fn error(action: &'static str, path: &path::Path, error: io::Error) -> io::Error {
+        io::Error::new(
+            error.kind(),
+            Self {
+                action,
+                error,
+                path: path.to_path_buf(),
+            },
+        )
+    }
+}
+
+impl fmt::Display for InputFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not {} input file: {}",
+            self.action,
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for InputFile {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The error used in place of a broken pipe that should be handled quietly.
+///
+/// When converted into [`crate::error::Error`], the error is marked as quiet and uses the exit
+/// status code chosen by the [`BrokenPipe`] policy.
+#[derive(Debug)]
+pub(crate) struct QuietPipe {
+    /// The message of the original error.
+    message: String,
+
+    /// The exit status code to use.
+    pub(crate) status: i32,
+}
+
+impl fmt::Display for QuietPipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for QuietPipe {}
+
+/// The error used when a stream is larger than the limit for reading it into memory.
+///
+/// When converted into [`crate::error::Error`], the error uses the OS error code for a file that is
+/// too large as the exit status code.
+#[derive(Debug)]
+pub(crate) struct TooLarge {
+    /// The maximum number of bytes that could be read.
+    limit: usize,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The stream is larger than the limit of {} bytes.",
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// Returns the contents of an in-memory stream as a lossy string.
+fn contents_string(stream: &Stream, name: &str) -> String {
+    if let StreamKind::Locked(shared) = &stream.inner {
+        return contents_string(&locked::lock(shared), name);
+    }
+
+    let contents = stream
+        .contents()
+        .unwrap_or_else(|| panic!("The {} stream is not an in-memory buffer.", name));
+
+    String::from_utf8_lossy(contents).to_string()
+}
+
+/// Checks if the path refers to a standard stream (i.e. `-`).
+fn is_standard(path: &path::Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Creates an error for an operation that the stream does not support.
+fn unsupported(operation: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("The stream does not support {}.", operation),
+    )
 }
 
 /// Manages a collection of input and output streams for a command.
 ///
 /// ```
 /// use carli::error::Result;
-/// use carli::io::{self, Shared, Streams};
+/// use carli::io::{self, Shared, Streams};
+/// use std::io::Write;
+///
+/// fn example(streams: &Streams) -> Result<()> {
+///     writeln!(streams.output(), "Hello, world!")?;
+///
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let streams = io::standard();
+///
+///     example(&streams).unwrap();
+/// }
+/// ```
+pub struct Streams {
+    /// The error output stream.
+    error: cell::RefCell<Stream>,
+
+    /// The input stream.
+    input: cell::RefCell<Stream>,
+
+    // The global output stream.
+    output: cell::RefCell<Stream>,
+}
+
+impl Streams {
+    /// Creates a new builder for an instance with streams chosen for each slot.
+    ///
+    /// ```
+    /// use carli::io::Streams;
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder().input(b"example".to_vec()).build();
+    /// # }
+    /// ```
+    pub fn builder() -> StreamsBuilder {
+        StreamsBuilder::default()
+    }
+
+    /// Returns the contents of the error output stream as a lossy string.
+    ///
+    /// The contents are returned without reading from the stream, so it does not matter what the
+    /// current position in the stream is. See [`Stream::contents`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error output stream is not an in-memory buffer, or is currently borrowed.
+    ///
+    /// ```
+    /// use carli::errorln;
+    /// use carli::io;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory();
+    ///
+    /// errorln!(streams, "Something went wrong.").unwrap();
+    ///
+    /// assert_eq!(streams.error_string(), "Something went wrong.\n");
+    /// # }
+    /// ```
+    pub fn error_string(&self) -> String {
+        contents_string(&self.error.borrow(), "error output")
+    }
+
+    /// Returns the contents of the global output stream as a lossy string.
+    ///
+    /// The contents are returned without reading from the stream, so it does not matter what the
+    /// current position in the stream is. See [`Stream::contents`] for more information.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global output stream is not an in-memory buffer, or is currently borrowed.
+    ///
+    /// ```
+    /// use carli::io;
+    /// use carli::outputln;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory();
+    ///
+    /// outputln!(streams, "Hello, world!").unwrap();
+    ///
+    /// assert_eq!(streams.output_string(), "Hello, world!\n");
+    /// # }
+    /// ```
+    pub fn output_string(&self) -> String {
+        contents_string(&self.output.borrow(), "global output")
+    }
+
+    /// Sets the policy for handling a broken pipe when writing to the global output stream.
+    ///
+    /// See [`BrokenPipe`] for more information.
+    ///
+    /// ```
+    /// use carli::io::{self, BrokenPipe};
+    ///
+    /// # fn main() {
+    /// let streams = io::standard();
+    ///
+    /// streams.set_broken_pipe(BrokenPipe::Quiet(141));
+    /// # }
+    /// ```
+    pub fn set_broken_pipe(&self, policy: BrokenPipe) {
+        self.output.borrow_mut().set_broken_pipe(policy);
+    }
+
+    /// Sets the choice for when styles are applied to the error and global output streams.
+    ///
+    /// ```
+    /// use carli::io;
+    /// use carli::style::ColorChoice;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory();
+    ///
+    /// streams.set_color(ColorChoice::Always);
+    /// # }
+    /// ```
+    pub fn set_color(&self, color: style::ColorChoice) {
+        self.error.borrow_mut().set_color(color);
+        self.output.borrow_mut().set_color(color);
+    }
+
+    /// Sets the limit on how many bytes [`Stream::to_string`] and [`Stream::to_text`] may read from
+    /// the input stream.
+    ///
+    /// See [`Stream::set_read_limit`] for more information.
+    ///
+    /// ```
+    /// use carli::io;
+    ///
+    /// # fn main() {
+    /// let streams = io::standard();
+    ///
+    /// streams.set_read_limit(Some(64 * 1024 * 1024));
+    /// # }
+    /// ```
+    pub fn set_read_limit(&self, limit: Option<usize>) {
+        self.input.borrow_mut().set_read_limit(limit);
+    }
+
+    /// Replaces the error output stream with one that discards everything written to it.
+    ///
+    /// ```
+    /// use carli::io;
+    ///
+    /// # fn main() {
+    /// let streams = io::standard().silence_error();
+    /// # }
+    /// ```
+    pub fn silence_error(self) -> Self {
+        self.error.replace(Stream::null());
+
+        self
+    }
+
+    /// Replaces the global output stream with one that discards everything written to it.
+    ///
+    /// ```
+    /// use carli::io;
+    ///
+    /// # fn main() {
+    /// let quiet = true;
+    /// let mut streams = io::standard();
+    ///
+    /// if quiet {
+    ///     streams = streams.silence_output();
+    /// }
+    /// # }
+    /// ```
+    pub fn silence_output(self) -> Self {
+        self.output.replace(Stream::null());
+
+        self
+    }
+
+    /// Returns a context that shares the streams with other threads.
+    ///
+    /// Each stream is moved behind a lock, and this instance continues to use the same streams by
+    /// locking them for every operation. See [`SyncStreams`] for more information.
+    ///
+    /// ```
+    /// use carli::io::{self, SyncShared};
+    /// use std::io::Write;
+    /// use std::thread;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory();
+    /// let shared = streams.sync();
+    ///
+    /// thread::spawn(move || writeln!(shared.output(), "Hello, world!").unwrap())
+    ///     .join()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(streams.output_string(), "Hello, world!\n");
+    /// # }
+    /// ```
+    pub fn sync(&self) -> SyncStreams {
+        SyncStreams::new(self)
+    }
+
+    /// Creates a new instance using the given input stream and in-memory buffers for the error
+    /// and global output streams.
+    ///
+    /// This is most useful when testing a command that reads its input in a particular way, such
+    /// as one chunk at a time using [`Stream::queued`]. For input that is available all at once,
+    /// [`memory_with_input`] is simpler.
+    ///
+    /// ```
+    /// use carli::io::{Shared, Stream, Streams};
+    ///
+    /// # fn main() {
+    /// let streams = Streams::with_input(Stream::queued(["yes\n", "no\n"]));
+    ///
+    /// assert_eq!(streams.input().to_string().unwrap(), "yes\nno\n");
+    /// # }
+    /// ```
+    pub fn with_input<S: Into<Stream>>(input: S) -> Self {
+        Self::new(Vec::new(), input, Vec::new())
+    }
+
+    /// Creates a new instance using the given streams.
+    fn new<E, I, O>(error: E, input: I, output: O) -> Self
+    where
+        E: Into<Stream>,
+        I: Into<Stream>,
+        O: Into<Stream>,
+    {
+        Self {
+            error: cell::RefCell::new(error.into()),
+            input: cell::RefCell::new(input.into()),
+            output: cell::RefCell::new(output.into()),
+        }
+    }
+}
+
+impl Shared for Streams {
+    fn error(&self) -> cell::RefMut<'_, Stream> {
+        self.error.borrow_mut()
+    }
+
+    fn input(&self) -> cell::RefMut<'_, Stream> {
+        self.input.borrow_mut()
+    }
+
+    fn output(&self) -> cell::RefMut<'_, Stream> {
+        self.output.borrow_mut()
+    }
+}
+
+/// Builds an instance of [`Streams`] using any combination of streams.
+///
+/// Each of the error, input, and output streams may be set to anything that can be converted into
+/// a [`Stream`]. Any stream that has not been set will fall back to its standard stream, which
+/// makes it possible to replace only the streams that matter for a particular use case.
+///
+/// ```
+/// use carli::io::{Shared, Stream, Streams};
+/// use std::io::{self, Write};
+///
+/// # fn main() {
+/// // Use an in-memory buffer for input and the standard streams for everything else.
+/// let streams = Streams::builder()
+///     .input(b"example".to_vec())
+///     .build();
+///
+/// let string = streams.input().to_string().unwrap();
+///
+/// writeln!(streams.output(), "{}", string).unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct StreamsBuilder {
+    /// The error output stream.
+    error: Option<Stream>,
+
+    /// The input stream.
+    input: Option<Stream>,
+
+    /// The global output stream.
+    output: Option<Stream>,
+
+    /// The maximum number of bytes read into memory at once from the input stream.
+    read_limit: Option<usize>,
+}
+
+impl StreamsBuilder {
+    /// Creates the instance of [`Streams`] using the configured streams.
+    ///
+    /// Any stream that was not configured will use the standard stream for its slot.
+    ///
+    /// ```
+    /// use carli::io::Streams;
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder().build();
+    /// # }
+    /// ```
+    pub fn build(self) -> Streams {
+        let streams = Streams::new(
+            self.error.unwrap_or_else(|| io::stderr().into()),
+            self.input.unwrap_or_else(|| io::stdin().into()),
+            self.output.unwrap_or_else(|| io::stdout().into()),
+        );
+
+        if self.read_limit.is_some() {
+            streams.set_read_limit(self.read_limit);
+        }
+
+        streams
+    }
+
+    /// Sets the error output stream.
+    ///
+    /// ```no_run
+    /// use carli::io::{Stream, Streams};
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder()
+    ///     .error(Stream::append("error.log").unwrap())
+    ///     .build();
+    /// # }
+    /// ```
+    pub fn error<S: Into<Stream>>(mut self, stream: S) -> Self {
+        self.error = Some(stream.into());
+
+        self
+    }
+
+    /// Sets the input stream.
+    ///
+    /// ```
+    /// use carli::io::Streams;
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder().input(b"example".to_vec()).build();
+    /// # }
+    /// ```
+    pub fn input<S: Into<Stream>>(mut self, stream: S) -> Self {
+        self.input = Some(stream.into());
+
+        self
+    }
+
+    /// Sets the global output stream.
+    ///
+    /// ```
+    /// use carli::io::Streams;
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder().output(Vec::new()).build();
+    /// # }
+    /// ```
+    pub fn output<S: Into<Stream>>(mut self, stream: S) -> Self {
+        self.output = Some(stream.into());
+
+        self
+    }
+
+    /// Sets the limit on how many bytes [`Stream::to_string`] and [`Stream::to_text`] may read from
+    /// the input stream.
+    ///
+    /// See [`Stream::set_read_limit`] for more information.
+    ///
+    /// ```
+    /// use carli::io::{Shared, Streams};
+    ///
+    /// # fn main() {
+    /// let streams = Streams::builder()
+    ///     .input(b"example".to_vec())
+    ///     .read_limit(4)
+    ///     .build();
+    ///
+    /// assert!(streams.input().to_string().is_err());
+    /// # }
+    /// ```
+    pub fn read_limit(mut self, limit: usize) -> Self {
+        self.read_limit = Some(limit);
+
+        self
+    }
+}
+
+/// Creates a new instance of [`Streams`] using in-memory buffers.
+///
+/// ```
+/// use carli::io;
+///
+/// # fn main() {
+/// let streams = io::memory();
+/// # }
+/// ```
+pub fn memory() -> Streams {
+    Streams::new(Vec::new(), Vec::new(), Vec::new())
+}
+
+/// Creates a new instance of [`Streams`] using in-memory buffers, with the input already loaded.
+///
+/// The input can be read from the start without having to write to the input stream and then
+/// seek back first.
+///
+/// ```
+/// use carli::io::{self, Shared};
+///
+/// # fn main() {
+/// let streams = io::memory_with_input("Hello, world!");
+///
+/// assert_eq!(streams.input().to_string().unwrap(), "Hello, world!");
+/// # }
+/// ```
+pub fn memory_with_input<B: AsRef<[u8]>>(input: B) -> Streams {
+    Streams::with_input(input.as_ref().to_vec())
+}
+
+/// Opens a file for reading, or uses the standard input stream if the path is `-`.
+///
+/// Many commands accept a path to read from, where `-` is used to read from the standard input
+/// stream instead. If the file cannot be opened, the error includes the path in its context.
+///
+/// ```no_run
+/// use carli::error::Result;
+/// use carli::io;
+///
+/// fn example(path: &str) -> Result<()> {
+///     let mut input = io::open_input(path)?;
+///
+///     println!("{}", input.to_string()?);
+///
+///     Ok(())
+/// }
+/// ```
+pub fn open_input<P: AsRef<path::Path>>(path: P) -> error::Result<Stream> {
+    let path = path.as_ref();
+
+    if is_standard(path) {
+        return Ok(io::stdin().into());
+    }
+
+    Stream::open(path).map_err(|error| {
+        Error::from(error).context(format!("Could not open input file: {}", path.display()))
+    })
+}
+
+/// Opens several files for reading as a single stream, in the same way that `cat` would.
+///
+/// Each path is opened like [`open_input`] would, so `-` may be used to read from the standard
+/// input stream. Every file is checked before the stream is returned, so that a missing file is
+/// reported before anything has been read. The stream is read one file at a time, in the order
+/// that the paths were given, as if all of them had been joined together into one file. Each file
+/// is only opened once the one before it has been read, and an error that occurs while reading
+/// includes the path of the file.
+///
+/// ```no_run
+/// use carli::error::Result;
+/// use carli::io;
+///
+/// fn example(paths: &[String]) -> Result<()> {
+///     let mut input = io::open_inputs(paths)?;
+///
+///     for line in input.lines() {
+///         println!("{}", line?);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn open_inputs<I, P>(paths: I) -> error::Result<Stream>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<path::Path>,
+{
+    let paths = paths
+        .into_iter()
+        .map(|path| {
+            let path = path.as_ref();
+
+            if !is_standard(path) {
+                fs::metadata(path).map_err(|error| {
+                    Error::from(error)
+                        .context(format!("Could not open input file: {}", path.display()))
+                })?;
+            }
+
+            Ok(path.to_path_buf())
+        })
+        .collect::<error::Result<_>>()?;
+
+    Ok(Stream::reader(Concat {
+        current: None,
+        paths,
+    }))
+}
+
+/// Creates a file for writing, or uses the standard output stream if the path is `-`.
+///
+/// The file is created if it does not already exist, and truncated if it does. If the file cannot
+/// be created, the error includes the path in its context.
+///
+/// ```no_run
+/// use carli::error::Result;
+/// use carli::io;
 /// use std::io::Write;
 ///
-/// fn example(streams: &Streams) -> Result<()> {
-///     writeln!(streams.output(), "Hello, world!")?;
+/// fn example(path: &str) -> Result<()> {
+///     let mut output = io::open_output(path)?;
+///
+///     writeln!(output, "Hello, world!")?;
 ///
 ///     Ok(())
 /// }
+/// ```
+pub fn open_output<P: AsRef<path::Path>>(path: P) -> error::Result<Stream> {
+    let path = path.as_ref();
+
+    if is_standard(path) {
+        return Ok(io::stdout().into());
+    }
+
+    Stream::create(path).map_err(|error| {
+        Error::from(error).context(format!("Could not open output file: {}", path.display()))
+    })
+}
+
+/// Creates a new instance of [`Streams`] using the standard streams.
 ///
-/// fn main() {
-///     let streams = io::standard();
+/// ```
+/// use carli::io;
 ///
-///     example(&streams).unwrap();
-/// }
+/// # fn main() {
+/// let streams = io::standard();
+/// # }
 /// ```
-pub struct Streams {
-    /// The error output stream.
-    error: cell::RefCell<Stream>,
+pub fn standard() -> Streams {
+    Streams::new(io::stderr(), io::stdin(), io::stdout())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Inspect;
+    use std::io::{Read, Seek, Write};
+
+    /// The lock held by tests using atomic streams, since uncommitted files are discarded globally.
+    static ATOMIC: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// The lock held by tests using pagers, since running pagers are closed globally.
+    #[cfg(unix)]
+    static PAGER: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Returns a unique path in the temporary directory for the test.
+    fn temp_path(name: &str) -> path::PathBuf {
+        std::env::temp_dir().join(format!("carli-{}-{}", std::process::id(), name))
+    }
+
+    fn create_streams() -> Streams {
+        Streams {
+            error: cell::RefCell::new(Stream::new(StreamKind::Memory(Vec::new().into()))),
+            input: cell::RefCell::new(Stream::new(StreamKind::Memory(Vec::new().into()))),
+            output: cell::RefCell::new(Stream::new(StreamKind::Memory(Vec::new().into()))),
+        }
+    }
+
+    #[test]
+    fn open_input_file() {
+        let path = temp_path("open_input_file");
+
+        fs::write(&path, "test").unwrap();
+
+        assert_eq!(open_input(&path).unwrap().to_string().unwrap(), "test");
+
+        fs::remove_file(&path).unwrap();
+
+        let error = open_input(&path).unwrap_err();
+
+        assert_eq!(
+            error.get_context(),
+            Some(vec![format!(
+                "Could not open input file: {}",
+                path.display()
+            )
+            .as_str()])
+        );
+    }
+
+    #[test]
+    fn open_input_standard() {
+        assert!(matches!(
+            open_input("-").unwrap().inner,
+            StreamKind::Stdin(_)
+        ));
+    }
+
+    #[test]
+    fn open_inputs_files() {
+        let first = temp_path("open_inputs_files_first");
+        let second = temp_path("open_inputs_files_second");
+
+        fs::write(&first, "first\n").unwrap();
+        fs::write(&second, "").unwrap();
+
+        let mut stream = open_inputs([&first, &second, &first]).unwrap();
+
+        assert_eq!(stream.to_string().unwrap(), "first\nfirst\n");
+
+        fs::remove_file(&second).unwrap();
+
+        let error = open_inputs([&first, &second]).unwrap_err();
+
+        assert_eq!(
+            error.get_context(),
+            Some(vec![format!(
+                "Could not open input file: {}",
+                second.display()
+            )
+            .as_str()])
+        );
+
+        fs::remove_file(first).unwrap();
+    }
+
+    #[test]
+    fn open_inputs_lazily() {
+        let first = temp_path("open_inputs_lazily_first");
+        let second = temp_path("open_inputs_lazily_second");
+
+        fs::write(&first, "first\n").unwrap();
+        fs::write(&second, "second\n").unwrap();
+
+        let mut stream = open_inputs([&first, &second]).unwrap();
+
+        fs::remove_file(&second).unwrap();
+
+        let mut lines = stream.lines();
+
+        assert_eq!(lines.read_line().unwrap().unwrap(), "first");
+
+        let error = lines.read_line().unwrap().unwrap_err();
+
+        assert_eq!(error.get_status(), 2);
+        assert_eq!(
+            error.get_context().unwrap()[0],
+            format!("Could not open input file: {}", second.display())
+        );
+
+        fs::remove_file(first).unwrap();
+    }
+
+    #[test]
+    fn open_output_file() {
+        let path = temp_path("open_output_file");
+
+        write!(open_output(&path).unwrap(), "test").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "test");
+
+        fs::remove_file(&path).unwrap();
+
+        let missing = path.join("missing");
+        let error = open_output(&missing).unwrap_err();
+
+        assert_eq!(
+            error.get_context(),
+            Some(vec![format!(
+                "Could not open output file: {}",
+                missing.display()
+            )
+            .as_str()])
+        );
+    }
+
+    #[test]
+    fn open_output_standard() {
+        assert!(matches!(
+            open_output("-").unwrap().inner,
+            StreamKind::Stdout(_)
+        ));
+    }
+
+    #[test]
+    fn stream_from_buffer() {
+        let _: Stream = Vec::new().into();
+    }
+
+    #[test]
+    fn stream_from_file() {
+        let path = temp_path("stream_from_file");
+        let file = fs::File::create(&path).unwrap();
+        let stream: Stream = file.into();
+
+        assert!(matches!(stream.inner, StreamKind::File(_)));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_from_stderr() {
+        let _: Stream = io::stderr().into();
+    }
+
+    #[test]
+    fn stream_from_stdin() {
+        let _: Stream = io::stdin().into();
+    }
+
+    #[test]
+    fn stream_from_stdout() {
+        let _: Stream = io::stdout().into();
+    }
+
+    #[test]
+    fn stream_broken_pipe() {
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .fail_write(2, io::ErrorKind::BrokenPipe.into())
+            .into();
+
+        write!(stream, "test").unwrap();
+
+        assert!(!stream.is_broken_pipe());
+
+        stream.set_broken_pipe(BrokenPipe::Quiet(0));
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert!(stream.is_broken_pipe());
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        assert!(error.get_ref().unwrap().is::<QuietPipe>());
+    }
+
+    #[test]
+    fn stream_atomic() {
+        let _lock = ATOMIC.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_atomic");
+
+        fs::write(&path, "previous contents").unwrap();
+
+        {
+            let mut stream = Stream::atomic(&path).unwrap();
+
+            write!(stream, "test").unwrap();
+            stream.flush().unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "previous contents");
+
+            stream.commit().unwrap();
+
+            assert!(matches!(stream.inner, StreamKind::File(_)));
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "test");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_atomic_commit_failed() {
+        let _lock = ATOMIC.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_atomic_commit_failed");
+
+        fs::create_dir(&path).unwrap();
+
+        let mut stream = Stream::atomic(&path).unwrap();
+
+        write!(stream, "first").unwrap();
+
+        assert!(stream.commit().is_err());
+        assert!(matches!(stream.inner, StreamKind::Atomic(_)));
+
+        write!(stream, " second").unwrap();
+
+        fs::remove_dir(&path).unwrap();
+
+        stream.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first second");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_atomic_discarded() {
+        let _lock = ATOMIC.lock().unwrap_or_else(|error| error.into_inner());
+        let directory = temp_path("stream_atomic_discarded");
+        let path = directory.join("target");
+
+        fs::create_dir(&directory).unwrap();
+
+        {
+            let mut stream = Stream::atomic(&path).unwrap();
+
+            write!(stream, "test").unwrap();
+
+            assert_eq!(fs::read_dir(&directory).unwrap().count(), 1);
+        }
+
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+
+        let mut stream = Stream::atomic(&path).unwrap();
+
+        write!(stream, "test").unwrap();
+
+        discard_uncommitted();
+
+        assert_eq!(fs::read_dir(&directory).unwrap().count(), 0);
+
+        drop(stream);
+
+        fs::remove_dir(directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stream_atomic_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _lock = ATOMIC.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_atomic_permissions");
+
+        fs::write(&path, "previous contents").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        Stream::atomic(&path).unwrap().commit().unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+
+        assert_eq!(mode & 0o777, 0o640);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_capabilities() {
+        let capabilities = |stream: Stream| {
+            let capabilities = stream.capabilities();
+
+            (
+                capabilities.is_readable(),
+                capabilities.is_seekable(),
+                capabilities.is_writable(),
+            )
+        };
+
+        assert_eq!(capabilities(Vec::new().into()), (true, true, true));
+        assert_eq!(
+            capabilities(Stream::reader(io::empty())),
+            (true, false, false)
+        );
+        assert_eq!(
+            capabilities(Stream::writer(io::sink())),
+            (false, false, true)
+        );
+        assert_eq!(
+            capabilities(Stream::custom(io::Cursor::new(Vec::new()))),
+            (true, true, true)
+        );
+        assert_eq!(capabilities(io::stderr().into()), (false, false, true));
+        assert_eq!(capabilities(io::stdin().into()), (true, false, false));
+        assert_eq!(capabilities(io::stdout().into()), (false, false, true));
+    }
+
+    #[test]
+    fn stream_custom() {
+        let mut stream = Stream::custom(io::Cursor::new(Vec::new()));
+
+        write!(stream, "test").unwrap();
+
+        stream.seek(io::SeekFrom::Start(2)).unwrap();
+
+        assert_eq!(stream.to_string().unwrap(), "st");
+    }
+
+    #[test]
+    fn stream_custom_reader() {
+        let mut stream = Stream::reader(&b"test"[..]);
+
+        assert_eq!(stream.to_string().unwrap(), "test");
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn stream_custom_writer() {
+        use std::sync::{Arc, Mutex};
+
+        /// A writer that shares its buffer with the test.
+        struct Shared(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for Shared {
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+
+            fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buffer)
+            }
+        }
+
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let mut stream = Stream::writer(Shared(buffer.clone()));
+
+        write!(stream, "test").unwrap();
+
+        assert_eq!(*buffer.lock().unwrap(), b"test");
+
+        let error = stream.read(&mut [0; 4]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn stream_file_append() {
+        let path = temp_path("stream_file_append");
+
+        fs::write(&path, "first\n").unwrap();
+
+        {
+            let mut stream = Stream::append(&path).unwrap();
+
+            writeln!(stream, "second").unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_file_create() {
+        let path = temp_path("stream_file_create");
+
+        fs::write(&path, "previous contents").unwrap();
+
+        {
+            let mut stream = Stream::create(&path).unwrap();
+
+            write!(stream, "test").unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "test");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_file_create_new() {
+        let path = temp_path("stream_file_create_new");
+
+        {
+            let mut stream = Stream::create_new(&path).unwrap();
+
+            write!(stream, "test").unwrap();
+
+            stream.rewind().unwrap();
+
+            assert_eq!(stream.to_string().unwrap(), "test");
+        }
+
+        let error = Stream::create_new(&path).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_file_open() {
+        let path = temp_path("stream_file_open");
+
+        fs::write(&path, "test").unwrap();
+
+        let mut stream = Stream::open(&path).unwrap();
+
+        stream.seek(io::SeekFrom::Start(2)).unwrap();
+
+        assert_eq!(stream.to_string_lossy(), "st");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn stream_is_terminal() {
+        let mut stream: Stream = Vec::new().into();
+
+        assert!(!stream.is_terminal());
+
+        stream.set_terminal(true);
+
+        assert!(stream.is_terminal());
+
+        let mut stream: Stream = io::stdout().into();
+
+        stream.set_terminal(false);
+
+        assert!(!stream.is_terminal());
+    }
+
+    #[test]
+    fn stream_memory_positions() {
+        let mut stream: Stream = b"first ".to_vec().into();
+
+        write!(stream, "second").unwrap();
+
+        let mut buffer = [0; 6];
 
-    /// The input stream.
-    input: cell::RefCell<Stream>,
+        stream.read_exact(&mut buffer).unwrap();
 
-    // The global output stream.
-    output: cell::RefCell<Stream>,
-}
+        assert_eq!(&buffer, b"first ");
 
-impl Streams {
-    /// Creates a new instance using the given streams.
-    fn new<E, I, O>(error: E, input: I, output: O) -> Self
-    where
-        E: Into<Stream>,
-        I: Into<Stream>,
-        O: Into<Stream>,
-    {
-        Self {
-            error: cell::RefCell::new(error.into()),
-            input: cell::RefCell::new(input.into()),
-            output: cell::RefCell::new(output.into()),
-        }
-    }
-}
+        write!(stream, " third").unwrap();
 
-impl Shared for Streams {
-    fn error(&self) -> cell::RefMut<Stream> {
-        self.error.borrow_mut()
+        assert_eq!(stream.to_string().unwrap(), "second third");
+        assert_eq!(stream.contents(), Some(&b"first second third"[..]));
+
+        stream.seek(io::SeekFrom::Current(-5)).unwrap();
+
+        write!(stream, "THIRD").unwrap();
+
+        assert_eq!(stream.to_string().unwrap(), "THIRD");
+        assert_eq!(stream.contents(), Some(&b"first second THIRD"[..]));
     }
 
-    fn input(&self) -> cell::RefMut<Stream> {
-        self.input.borrow_mut()
+    #[test]
+    fn stream_null() {
+        let mut stream: Stream = io::sink().into();
+
+        assert!(matches!(stream.inner, StreamKind::Null));
+
+        write!(stream, "test").unwrap();
+        stream.flush().unwrap();
+
+        assert_eq!(stream.seek(io::SeekFrom::End(0)).unwrap(), 0);
+        assert_eq!(stream.to_string().unwrap(), "");
     }
 
-    fn output(&self) -> cell::RefMut<Stream> {
-        self.output.borrow_mut()
+    /// Creates a fake pager that marks every line it is shown, and returns the command to run it.
+    #[cfg(unix)]
+    fn fake_pager(path: &path::Path) -> String {
+        fs::write(path, "sed 's/^/paged: /'\n").unwrap();
+
+        format!("sh {}", path.display())
     }
-}
 
-/// Creates a new instance of [`Streams`] using in-memory buffers.
-///
-/// ```
-/// use carli::io;
-///
-/// # fn main() {
-/// let streams = io::memory();
-/// # }
-/// ```
-pub fn memory() -> Streams {
-    Streams::new(Vec::new(), Vec::new(), Vec::new())
-}
+    #[cfg(unix)]
+    #[test]
+    fn stream_page() {
+        let _lock = PAGER.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_page");
+        let script = temp_path("stream_page.sh");
+        let pager = fake_pager(&script);
+        let mut stream = Stream::create(&path).unwrap();
 
-/// Creates a new instance of [`Streams`] using the standard streams.
-///
-/// ```
-/// use carli::io;
-///
-/// # fn main() {
-/// let streams = io::standard();
-/// # }
-/// ```
-pub fn standard() -> Streams {
-    Streams::new(io::stderr(), io::stdin(), io::stdout())
-}
+        writeln!(stream, "before").unwrap();
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use std::io::{Read, Seek, Write};
+        stream.set_terminal(true);
 
-    fn create_streams() -> Streams {
-        Streams {
-            error: cell::RefCell::new(Stream {
-                inner: StreamKind::Memory(io::Cursor::new(Vec::new())),
-            }),
-            input: cell::RefCell::new(Stream {
-                inner: StreamKind::Memory(io::Cursor::new(Vec::new())),
-            }),
-            output: cell::RefCell::new(Stream {
-                inner: StreamKind::Memory(io::Cursor::new(Vec::new())),
-            }),
-        }
+        assert!(stream.page_with(&pager).unwrap());
+        assert!(stream.is_terminal());
+        assert!(!stream.capabilities().is_readable());
+
+        write!(stream, "first\nsecond\n").unwrap();
+
+        stream.commit().unwrap();
+
+        assert!(matches!(stream.inner, StreamKind::File(_)));
+
+        writeln!(stream, "after").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "before\npaged: first\npaged: second\nafter\n"
+        );
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(script).unwrap();
     }
 
+    #[cfg(unix)]
     #[test]
-    fn stream_from_buffer() {
-        let _: Stream = Vec::new().into();
+    fn stream_page_dropped() {
+        let _lock = PAGER.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_page_dropped");
+        let script = temp_path("stream_page_dropped.sh");
+        let pager = fake_pager(&script);
+
+        {
+            let mut stream = Stream::create(&path).unwrap();
+
+            stream.set_terminal(true);
+            stream.page_with(&pager).unwrap();
+
+            writeln!(stream, "test").unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "paged: test\n");
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(script).unwrap();
     }
 
+    #[cfg(unix)]
     #[test]
-    fn stream_from_stderr() {
-        let _: Stream = io::stderr().into();
+    fn stream_page_waited() {
+        let _lock = PAGER.lock().unwrap_or_else(|error| error.into_inner());
+        let path = temp_path("stream_page_waited");
+        let script = temp_path("stream_page_waited.sh");
+        let pager = fake_pager(&script);
+        let mut stream = Stream::create(&path).unwrap();
+
+        stream.set_terminal(true);
+        stream.page_with(&pager).unwrap();
+
+        writeln!(stream, "test").unwrap();
+
+        wait_for_pagers();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "paged: test\n");
+        assert!(writeln!(stream, "after").is_err());
+
+        drop(stream);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(script).unwrap();
     }
 
+    #[cfg(unix)]
     #[test]
-    fn stream_from_stdin() {
-        let _: Stream = io::stdin().into();
+    fn stream_page_skipped() {
+        let path = temp_path("stream_page_skipped");
+        let script = temp_path("stream_page_skipped.sh");
+        let pager = fake_pager(&script);
+        let mut stream = Stream::create(&path).unwrap();
+
+        assert!(!stream.page_with(&pager).unwrap());
+
+        stream.set_terminal(true);
+
+        assert!(!stream.page_with("cat").unwrap());
+        assert!(!stream.page_with("carli-missing-pager").unwrap());
+        assert!(matches!(stream.inner, StreamKind::File(_)));
+
+        writeln!(stream, "test").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "test\n");
+
+        let mut stream: Stream = Vec::new().into();
+
+        stream.set_terminal(true);
+
+        assert!(!stream.page_with(&pager).unwrap());
+        assert!(matches!(stream.inner, StreamKind::Memory(_)));
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(script).unwrap();
     }
 
     #[test]
-    fn stream_from_stdout() {
-        let _: Stream = io::stdout().into();
+    fn stream_queued_empty() {
+        let mut stream = Stream::queued(["", "first\n", "", "", "second\n", ""]);
+        let mut buffer = [0; 64];
+
+        assert_eq!(stream.read(&mut buffer).unwrap(), 6);
+        assert_eq!(stream.read(&mut buffer).unwrap(), 7);
+        assert_eq!(stream.read(&mut buffer).unwrap(), 0);
     }
 
     #[test]
     fn stream_read() {
-        let mut stream = Stream {
-            inner: StreamKind::Memory(io::Cursor::new(b"test".to_vec())),
-        };
+        let mut stream = Stream::new(StreamKind::Memory(b"test".to_vec().into()));
 
         let mut buffer = Vec::new();
 
@@ -477,11 +3282,20 @@ mod test {
         assert_eq!(buffer, b"test");
     }
 
+    #[test]
+    fn stream_read_unsupported() {
+        let mut stream: Stream = io::stdout().into();
+        let mut buffer = Vec::new();
+
+        let error = stream.read_to_end(&mut buffer).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(error.to_string(), "The stream does not support reading.");
+    }
+
     #[test]
     fn stream_seek() {
-        let mut stream = Stream {
-            inner: StreamKind::Memory(io::Cursor::new(b"test".to_vec())),
-        };
+        let mut stream = Stream::new(StreamKind::Memory(b"test".to_vec().into()));
 
         stream.seek(io::SeekFrom::Start(2)).unwrap();
 
@@ -492,22 +3306,194 @@ mod test {
         assert_eq!(buffer, b"st");
     }
 
+    #[test]
+    fn stream_seek_unsupported() {
+        let mut stream: Stream = io::stdin().into();
+
+        let error = stream.seek(io::SeekFrom::Start(0)).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(error.to_string(), "The stream does not support seeking.");
+    }
+
+    #[test]
+    fn stream_spool() {
+        for threshold in [0, 3, 4, 1024, usize::MAX] {
+            let mut stream = Stream::reader(io::Cursor::new(b"test".to_vec()));
+
+            stream.spool(threshold).unwrap();
+
+            match threshold {
+                0 | 3 => assert!(matches!(stream.inner, StreamKind::File(_))),
+                _ => assert!(matches!(stream.inner, StreamKind::Memory(_))),
+            }
+
+            assert_eq!(stream.to_string().unwrap(), "test");
+
+            stream.rewind().unwrap();
+
+            assert_eq!(stream.to_string().unwrap(), "test");
+        }
+    }
+
+    #[test]
+    fn stream_spool_seekable() {
+        let mut stream: Stream = b"test".to_vec().into();
+        let mut buffer = [0; 2];
+
+        stream.read_exact(&mut buffer).unwrap();
+        stream.spool(0).unwrap();
+
+        assert!(matches!(stream.inner, StreamKind::Memory(_)));
+        assert_eq!(stream.to_string().unwrap(), "st");
+    }
+
+    #[test]
+    fn stream_tee() {
+        let mut stream = Stream::tee(
+            vec![Vec::new().into(), Vec::new().into()],
+            TeePolicy::FailFast,
+        );
+
+        write!(stream, "test").unwrap();
+        stream.flush().unwrap();
+
+        assert_eq!(
+            stream.capabilities(),
+            Capabilities {
+                readable: false,
+                seekable: false,
+                writable: true
+            }
+        );
+
+        for sink in stream.sinks_mut() {
+            assert_eq!(sink.contents(), Some(&b"test"[..]));
+        }
+
+        let error = stream.read(&mut [0; 4]).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn stream_tee_attach() {
+        let mut stream: Stream = Vec::new().into();
+
+        stream.set_terminal(true);
+        stream.attach(Vec::new());
+        stream.attach(Vec::new());
+
+        assert!(stream.is_terminal());
+        assert_eq!(stream.sinks_mut().len(), 3);
+
+        write!(stream, "test").unwrap();
+
+        for sink in stream.sinks_mut() {
+            assert_eq!(sink.contents(), Some(&b"test"[..]));
+        }
+    }
+
+    #[test]
+    fn stream_tee_attach_settings() {
+        let mut stream: Stream = Vec::new().into();
+
+        stream.set_broken_pipe(BrokenPipe::Quiet(0));
+        stream.set_read_limit(Some(4));
+        stream.set_terminal(true);
+        stream.attach(Faulty::new(Vec::new()).fail_write(1, io::ErrorKind::BrokenPipe.into()));
+
+        assert_eq!(stream.limit, Some(4));
+        assert_eq!(stream.terminal, Some(true));
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert!(stream.is_broken_pipe());
+        assert!(error.get_ref().unwrap().is::<QuietPipe>());
+    }
+
+    #[test]
+    fn stream_tee_best_effort() {
+        let mut stream = Stream::tee(
+            vec![io::stdin().into(), Vec::new().into()],
+            TeePolicy::BestEffort,
+        );
+
+        write!(stream, "test").unwrap();
+
+        assert_eq!(stream.sinks_mut()[1].contents(), Some(&b"test"[..]));
+
+        let mut stream = Stream::tee(
+            vec![io::stdin().into(), io::stdin().into()],
+            TeePolicy::BestEffort,
+        );
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+    }
+
+    #[test]
+    fn stream_tee_fail_fast() {
+        let mut stream = Stream::tee(
+            vec![io::stdin().into(), Vec::new().into()],
+            TeePolicy::FailFast,
+        );
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+
+        assert_eq!(stream.sinks_mut()[1].contents(), Some(&b""[..]));
+    }
+
     #[test]
     fn stream_to_string() {
-        let mut stream = Stream {
-            inner: StreamKind::Memory(io::Cursor::new(b"test".to_vec())),
-        };
+        let mut stream = Stream::new(StreamKind::Memory(b"test".to_vec().into()));
 
         let string = stream.to_string().unwrap();
 
         assert_eq!(string, "test");
     }
 
+    #[test]
+    fn stream_to_string_limited() {
+        let mut stream: Stream = b"test".to_vec().into();
+
+        assert_eq!(stream.to_string_limited(4).unwrap(), "test");
+
+        let mut stream: Stream = b"tests".to_vec().into();
+        let error = stream.to_string_limited(4).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            "The stream is larger than the limit of 4 bytes."
+        );
+
+        stream.set_read_limit(Some(5));
+
+        stream.rewind().unwrap();
+
+        assert_eq!(stream.to_string().unwrap(), "tests");
+
+        stream.set_read_limit(Some(3));
+
+        stream.rewind().unwrap();
+
+        assert!(stream.to_string().is_err());
+    }
+
+    #[test]
+    fn stream_to_string_limited_max() {
+        let mut stream: Stream = b"test".to_vec().into();
+
+        assert_eq!(stream.to_string_limited(usize::MAX).unwrap(), "test");
+    }
+
     #[test]
     fn stream_to_string_lossy() {
-        let mut stream = Stream {
-            inner: StreamKind::Memory(io::Cursor::new(b"test".to_vec())),
-        };
+        let mut stream = Stream::new(StreamKind::Memory(b"test".to_vec().into()));
 
         let string = stream.to_string_lossy();
 
@@ -515,36 +3501,89 @@ mod test {
     }
 
     #[test]
-    fn stream_write() {
-        let mut stream = Stream {
-            inner: StreamKind::Memory(io::Cursor::new(Vec::new())),
-        };
+    fn stream_to_string_lossy_limited() {
+        let mut stream: Stream = b"tests".to_vec().into();
 
-        write!(stream, "test").unwrap();
+        stream.set_read_limit(Some(4));
+
+        assert_eq!(stream.to_string_lossy(), "tests");
+    }
 
-        if let StreamKind::Memory(mut cursor) = stream.inner {
-            cursor.seek(io::SeekFrom::Start(0)).unwrap();
+    #[test]
+    fn stream_write() {
+        let mut stream = Stream::new(StreamKind::Memory(Vec::new().into()));
 
-            let vec = cursor.into_inner();
+        write!(stream, "test").unwrap();
 
-            assert_eq!(vec, b"test");
+        if let StreamKind::Memory(memory) = stream.inner {
+            assert_eq!(memory.buffer, b"test");
         } else {
             panic!("Unexpected StreamKind.");
         }
     }
 
+    #[test]
+    fn stream_write_unsupported() {
+        let mut stream: Stream = io::stdin().into();
+
+        let error = write!(stream, "test").unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+
+        let error = stream.flush().unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(error.to_string(), "The stream does not support flushing.");
+    }
+
+    #[test]
+    fn streams_builder() {
+        let streams = Streams::builder()
+            .error(Vec::new())
+            .input(b"test".to_vec())
+            .build();
+
+        assert!(matches!(streams.error().inner, StreamKind::Memory(_)));
+        assert!(matches!(streams.input().inner, StreamKind::Memory(_)));
+        assert!(matches!(streams.output().inner, StreamKind::Stdout(_)));
+
+        assert_eq!(streams.input().to_string().unwrap(), "test");
+
+        let streams = Streams::builder()
+            .input(b"test".to_vec())
+            .read_limit(3)
+            .build();
+
+        assert!(streams.input().to_string().is_err());
+
+        streams.set_read_limit(None);
+
+        streams.input().rewind().unwrap();
+
+        assert_eq!(streams.input().to_string().unwrap(), "test");
+    }
+
+    #[test]
+    fn streams_builder_default() {
+        let streams = Streams::builder().build();
+
+        assert!(matches!(streams.error().inner, StreamKind::Stderr(_)));
+        assert!(matches!(streams.input().inner, StreamKind::Stdin(_)));
+        assert!(matches!(streams.output().inner, StreamKind::Stdout(_)));
+    }
+
     #[test]
     fn streams_error() {
         let streams = create_streams();
 
         write!(streams.error(), "test").unwrap();
 
-        let cursor = match streams.error.into_inner().inner {
-            StreamKind::Memory(cursor) => cursor,
+        let memory = match streams.error.into_inner().inner {
+            StreamKind::Memory(memory) => memory,
             _ => panic!("Expected StreamKind::Memory."),
         };
 
-        assert_eq!(cursor.into_inner(), b"test");
+        assert_eq!(memory.buffer, b"test");
     }
 
     #[test]
@@ -566,6 +3605,71 @@ mod test {
         assert_eq!(buffer, b"test");
     }
 
+    #[test]
+    fn streams_input_preloaded() {
+        let streams = memory_with_input("test");
+
+        let mut buffer = Vec::new();
+
+        streams.input().read_to_end(&mut buffer).unwrap();
+
+        assert_eq!(buffer, b"test");
+        assert_eq!(streams.output_string(), "");
+    }
+
+    #[test]
+    fn streams_input_queued() {
+        let streams = Streams::with_input(Stream::queued(["first\n", "second\n"]));
+
+        let mut input = streams.input();
+        let mut buffer = [0; 64];
+
+        assert_eq!(input.contents(), Some(&b""[..]));
+        assert_eq!(input.read(&mut buffer).unwrap(), 6);
+        assert_eq!(input.contents(), Some(&b"first\n"[..]));
+
+        writeln!(input, "written").unwrap();
+
+        assert_eq!(input.read(&mut buffer).unwrap(), 8);
+        assert_eq!(&buffer[..8], b"written\n");
+        assert_eq!(input.to_string().unwrap(), "second\n");
+        assert_eq!(input.read(&mut buffer).unwrap(), 0);
+    }
+
+    #[test]
+    fn streams_contents_string() {
+        let streams = create_streams();
+
+        write!(streams.error(), "error").unwrap();
+        write!(streams.output(), "output").unwrap();
+
+        assert_eq!(streams.error_string(), "error");
+        assert_eq!(streams.output_string(), "output");
+        assert_eq!(streams.output().to_string().unwrap(), "output");
+        assert_eq!(streams.output_string(), "output");
+    }
+
+    #[test]
+    #[should_panic(expected = "The global output stream is not an in-memory buffer.")]
+    fn streams_contents_string_not_memory() {
+        Streams::builder().build().output_string();
+    }
+
+    #[test]
+    fn streams_is_interactive() {
+        let streams = create_streams();
+
+        assert!(!streams.is_interactive());
+
+        streams.input().set_terminal(true);
+
+        assert!(!streams.is_interactive());
+
+        streams.error().set_terminal(true);
+
+        assert!(streams.is_interactive());
+    }
+
     #[test]
     fn streams_memory() {
         let _: Streams = memory();
@@ -577,12 +3681,25 @@ mod test {
 
         write!(streams.output(), "test").unwrap();
 
-        let cursor = match streams.output.into_inner().inner {
-            StreamKind::Memory(cursor) => cursor,
+        let memory = match streams.output.into_inner().inner {
+            StreamKind::Memory(memory) => memory,
             _ => panic!("Expected StreamKind::Memory."),
         };
 
-        assert_eq!(cursor.into_inner(), b"test");
+        assert_eq!(memory.buffer, b"test");
+    }
+
+    #[test]
+    fn streams_silence() {
+        let streams = create_streams().silence_error();
+
+        assert!(matches!(streams.error().inner, StreamKind::Null));
+        assert!(matches!(streams.output().inner, StreamKind::Memory(_)));
+
+        let streams = create_streams().silence_output();
+
+        assert!(matches!(streams.error().inner, StreamKind::Memory(_)));
+        assert!(matches!(streams.output().inner, StreamKind::Null));
     }
 
     #[test]
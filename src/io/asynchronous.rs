@@ -0,0 +1,565 @@
+//! Provides asynchronous streams for commands that use [`tokio`].
+//!
+//! The types in this module mirror [`Stream`], [`super::Shared`], and [`super::Streams`], but
+//! implement [`AsyncRead`] and [`AsyncWrite`] so that commands can `.await` their input and output
+//! alongside other asynchronous work. Files and the standard streams use their [`tokio`]
+//! counterparts, while any [`Stream`] (such as an in-memory buffer) may be used as is, which keeps
+//! commands just as easy to test.
+
+use super::{unsupported, Stream};
+use std::{fmt, io, pin, task};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::sync::Mutex;
+
+/// A trait for objects that manage the shared asynchronous streams for a command.
+///
+/// This is the counterpart to [`super::Shared`] for commands that implement
+/// [`crate::command::AsyncExecute`]. Each stream is kept behind an asynchronous lock, which may be
+/// held across an `.await` and waited on by other tasks that want to use the same stream. Since
+/// commands may be spawned on a multi-threaded runtime, the context must be [`Sync`].
+///
+/// ```
+/// use carli::error::Result;
+/// use carli::io::AsyncShared;
+/// use tokio::io::AsyncWriteExt;
+///
+/// async fn example(streams: &dyn AsyncShared) -> Result<()> {
+///     streams.output().lock().await.write_all(b"Hello, world!\n").await?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait AsyncShared: Sync {
+    /// Returns the lock for the error output stream.
+    fn error(&self) -> &Mutex<AsyncStream>;
+
+    /// Returns the lock for the input stream.
+    fn input(&self) -> &Mutex<AsyncStream>;
+
+    /// Returns the lock for the global output stream.
+    fn output(&self) -> &Mutex<AsyncStream>;
+}
+
+/// The backing streams that are supported.
+enum AsyncKind {
+    /// Uses a stream that is read from and written to without waiting.
+    Blocking(Stream),
+
+    /// Uses [`tokio::fs::File`] for reading and writing.
+    File(tokio::fs::File),
+
+    /// Uses a custom reader provided by the application.
+    Reader(pin::Pin<Box<dyn AsyncRead + Send>>),
+
+    /// Uses [`tokio::io::Stderr`] for writing.
+    Stderr(tokio::io::Stderr),
+
+    /// Uses [`tokio::io::Stdin`] for reading.
+    Stdin(tokio::io::Stdin),
+
+    /// Uses [`tokio::io::Stdout`] for writing.
+    Stdout(tokio::io::Stdout),
+
+    /// Uses a custom writer provided by the application.
+    Writer(pin::Pin<Box<dyn AsyncWrite + Send>>),
+}
+
+impl fmt::Debug for AsyncKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blocking(stream) => f.debug_tuple("Blocking").field(stream).finish(),
+            Self::File(file) => f.debug_tuple("File").field(file).finish(),
+            Self::Reader(_) => f.write_str("Reader"),
+            Self::Stderr(_) => f.write_str("Stderr"),
+            Self::Stdin(_) => f.write_str("Stdin"),
+            Self::Stdout(_) => f.write_str("Stdout"),
+            Self::Writer(_) => f.write_str("Writer"),
+        }
+    }
+}
+
+/// An asynchronous stream replacement that supports reading and writing.
+///
+/// A [`Stream`] can be converted into this type, in which case it is read from and written to
+/// without waiting. This is meant for streams that never have to wait, such as in-memory buffers,
+/// so that commands can be tested in the same way as synchronous ones. Files and the standard
+/// streams should be converted from their [`tokio`] counterparts instead.
+///
+/// ```
+/// use carli::io::AsyncStream;
+/// use tokio::io::AsyncWriteExt;
+///
+/// async fn example() -> std::io::Result<()> {
+///     let mut stream: AsyncStream = Vec::new().into();
+///
+///     stream.write_all(b"Hello, world!").await?;
+///
+///     assert_eq!(stream.contents(), Some(&b"Hello, world!"[..]));
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncStream {
+    /// The backing stream.
+    inner: AsyncKind,
+
+    /// The flag used to override terminal detection.
+    terminal: Option<bool>,
+}
+
+impl From<Stream> for AsyncStream {
+    fn from(stream: Stream) -> Self {
+        Self::new(AsyncKind::Blocking(stream))
+    }
+}
+
+impl From<Vec<u8>> for AsyncStream {
+    fn from(buffer: Vec<u8>) -> Self {
+        Stream::from(buffer).into()
+    }
+}
+
+impl From<tokio::fs::File> for AsyncStream {
+    fn from(file: tokio::fs::File) -> Self {
+        Self::new(AsyncKind::File(file))
+    }
+}
+
+impl From<tokio::io::Stderr> for AsyncStream {
+    fn from(stderr: tokio::io::Stderr) -> Self {
+        Self::new(AsyncKind::Stderr(stderr))
+    }
+}
+
+impl From<tokio::io::Stdin> for AsyncStream {
+    fn from(stdin: tokio::io::Stdin) -> Self {
+        Self::new(AsyncKind::Stdin(stdin))
+    }
+}
+
+impl From<tokio::io::Stdout> for AsyncStream {
+    fn from(stdout: tokio::io::Stdout) -> Self {
+        Self::new(AsyncKind::Stdout(stdout))
+    }
+}
+
+impl AsyncRead for AsyncStream {
+    fn poll_read(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> task::Poll<io::Result<()>> {
+        match &mut self.get_mut().inner {
+            AsyncKind::Blocking(stream) => task::Poll::Ready(
+                io::Read::read(stream, buf.initialize_unfilled()).map(|read| {
+                    buf.advance(read);
+                }),
+            ),
+            AsyncKind::File(file) => pin::Pin::new(file).poll_read(cx, buf),
+            AsyncKind::Reader(reader) => reader.as_mut().poll_read(cx, buf),
+            AsyncKind::Stdin(stdin) => pin::Pin::new(stdin).poll_read(cx, buf),
+            _ => task::Poll::Ready(Err(unsupported("reading"))),
+        }
+    }
+}
+
+impl AsyncWrite for AsyncStream {
+    fn poll_flush(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> task::Poll<io::Result<()>> {
+        match &mut self.get_mut().inner {
+            AsyncKind::Blocking(stream) => task::Poll::Ready(io::Write::flush(stream)),
+            AsyncKind::File(file) => pin::Pin::new(file).poll_flush(cx),
+            AsyncKind::Stderr(stderr) => pin::Pin::new(stderr).poll_flush(cx),
+            AsyncKind::Stdout(stdout) => pin::Pin::new(stdout).poll_flush(cx),
+            AsyncKind::Writer(writer) => writer.as_mut().poll_flush(cx),
+            _ => task::Poll::Ready(Err(unsupported("flushing"))),
+        }
+    }
+
+    /// Finishes writing to the stream.
+    ///
+    /// A [`Stream`] that was converted into this type is committed (see [`Stream::commit`]).
+    fn poll_shutdown(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> task::Poll<io::Result<()>> {
+        match &mut self.get_mut().inner {
+            AsyncKind::Blocking(stream) => task::Poll::Ready(stream.commit()),
+            AsyncKind::File(file) => pin::Pin::new(file).poll_shutdown(cx),
+            AsyncKind::Stderr(stderr) => pin::Pin::new(stderr).poll_shutdown(cx),
+            AsyncKind::Stdout(stdout) => pin::Pin::new(stdout).poll_shutdown(cx),
+            AsyncKind::Writer(writer) => writer.as_mut().poll_shutdown(cx),
+            _ => task::Poll::Ready(Err(unsupported("writing"))),
+        }
+    }
+
+    fn poll_write(
+        self: pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buffer: &[u8],
+    ) -> task::Poll<io::Result<usize>> {
+        match &mut self.get_mut().inner {
+            AsyncKind::Blocking(stream) => task::Poll::Ready(io::Write::write(stream, buffer)),
+            AsyncKind::File(file) => pin::Pin::new(file).poll_write(cx, buffer),
+            AsyncKind::Stderr(stderr) => pin::Pin::new(stderr).poll_write(cx, buffer),
+            AsyncKind::Stdout(stdout) => pin::Pin::new(stdout).poll_write(cx, buffer),
+            AsyncKind::Writer(writer) => writer.as_mut().poll_write(cx, buffer),
+            _ => task::Poll::Ready(Err(unsupported("writing"))),
+        }
+    }
+}
+
+impl AsyncStream {
+    /// Returns the contents of an in-memory buffer without reading from it.
+    ///
+    /// See [`Stream::contents`] for more information. If the stream is not an in-memory buffer,
+    /// [`None`] is returned.
+    pub fn contents(&self) -> Option<&[u8]> {
+        match &self.inner {
+            AsyncKind::Blocking(stream) => stream.contents(),
+            _ => None,
+        }
+    }
+
+    /// Checks if the stream is connected to a terminal.
+    ///
+    /// See [`Stream::is_terminal`] for more information.
+    pub fn is_terminal(&self) -> bool {
+        use std::io::IsTerminal;
+
+        if let Some(terminal) = self.terminal {
+            return terminal;
+        }
+
+        match &self.inner {
+            AsyncKind::Blocking(stream) => stream.is_terminal(),
+            AsyncKind::Stderr(_) => io::stderr().is_terminal(),
+            AsyncKind::Stdin(_) => io::stdin().is_terminal(),
+            AsyncKind::Stdout(_) => io::stdout().is_terminal(),
+            _ => false,
+        }
+    }
+
+    /// Creates a stream using a custom asynchronous reader.
+    ///
+    /// The stream will only support reading, which makes it possible to read from something like
+    /// a network socket.
+    ///
+    /// ```
+    /// use carli::io::AsyncStream;
+    ///
+    /// # fn main() {
+    /// let stream = AsyncStream::reader(tokio::io::empty());
+    /// # }
+    /// ```
+    pub fn reader<R: AsyncRead + Send + 'static>(reader: R) -> Self {
+        Self::new(AsyncKind::Reader(Box::pin(reader)))
+    }
+
+    /// Overrides terminal detection for the stream.
+    ///
+    /// See [`Stream::set_terminal`] for more information.
+    pub fn set_terminal(&mut self, terminal: bool) {
+        self.terminal = Some(terminal);
+    }
+
+    /// Reads the stream into a string.
+    ///
+    /// This method will read from the current position in the stream all the way to the end. The
+    /// contents that have been read will then be parsed as a [`String`] and the result is returned
+    /// as is.
+    ///
+    /// ```
+    /// use carli::error::Result;
+    /// use carli::io::AsyncStream;
+    ///
+    /// async fn example(stream: &mut AsyncStream) -> Result<()> {
+    ///     let string = stream.to_string().await?;
+    ///
+    ///     println!("{}", string);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn to_string(&mut self) -> Result<String, io::Error> {
+        use tokio::io::AsyncReadExt;
+
+        let mut buffer = Vec::new();
+
+        self.read_to_end(&mut buffer).await?;
+
+        String::from_utf8(buffer).map_err(|error| io::Error::new(io::ErrorKind::Other, error))
+    }
+
+    /// Creates a stream using a custom asynchronous writer.
+    ///
+    /// The stream will only support writing, which makes it possible to write to something like
+    /// a network socket.
+    ///
+    /// ```
+    /// use carli::io::AsyncStream;
+    ///
+    /// # fn main() {
+    /// let stream = AsyncStream::writer(tokio::io::sink());
+    /// # }
+    /// ```
+    pub fn writer<W: AsyncWrite + Send + 'static>(writer: W) -> Self {
+        Self::new(AsyncKind::Writer(Box::pin(writer)))
+    }
+
+    /// Creates a new instance using the given backing stream.
+    fn new(inner: AsyncKind) -> Self {
+        Self {
+            inner,
+            terminal: None,
+        }
+    }
+}
+
+/// Manages a collection of asynchronous input and output streams for a command.
+///
+/// ```
+/// use carli::error::Result;
+/// use carli::io::{AsyncShared, AsyncStreams};
+/// use tokio::io::AsyncWriteExt;
+///
+/// async fn example(streams: &AsyncStreams) -> Result<()> {
+///     streams.output().lock().await.write_all(b"Hello, world!\n").await?;
+///
+///     Ok(())
+/// }
+///
+/// fn main() {
+///     let streams = AsyncStreams::memory();
+///
+///     // Using a runtime such as the one created by #[tokio::main].
+///     # let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///     runtime.block_on(example(&streams)).unwrap();
+///
+///     assert_eq!(streams.output_string(), "Hello, world!\n");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncStreams {
+    /// The error output stream.
+    error: Mutex<AsyncStream>,
+
+    /// The input stream.
+    input: Mutex<AsyncStream>,
+
+    /// The global output stream.
+    output: Mutex<AsyncStream>,
+}
+
+impl AsyncStreams {
+    /// Returns the contents of the error output stream as a lossy string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error output stream is not an in-memory buffer, or is currently locked.
+    pub fn error_string(&self) -> String {
+        contents_string(&self.error, "error output")
+    }
+
+    /// Creates a new instance using in-memory buffers.
+    ///
+    /// ```
+    /// use carli::io::AsyncStreams;
+    ///
+    /// # fn main() {
+    /// let streams = AsyncStreams::memory();
+    /// # }
+    /// ```
+    pub fn memory() -> Self {
+        Self::new(Vec::new(), Vec::new(), Vec::new())
+    }
+
+    /// Creates a new instance using in-memory buffers, with the input already loaded.
+    ///
+    /// ```
+    /// use carli::io::AsyncStreams;
+    ///
+    /// # fn main() {
+    /// let streams = AsyncStreams::memory_with_input("example");
+    /// # }
+    /// ```
+    pub fn memory_with_input<B: AsRef<[u8]>>(input: B) -> Self {
+        Self::new(Vec::new(), input.as_ref().to_vec(), Vec::new())
+    }
+
+    /// Creates a new instance using the given streams.
+    ///
+    /// ```
+    /// use carli::io::{AsyncStreams, Stream};
+    ///
+    /// # fn main() {
+    /// let streams = AsyncStreams::new(Vec::new(), Stream::queued(["yes\n"]), Vec::new());
+    /// # }
+    /// ```
+    pub fn new<E, I, O>(error: E, input: I, output: O) -> Self
+    where
+        E: Into<AsyncStream>,
+        I: Into<AsyncStream>,
+        O: Into<AsyncStream>,
+    {
+        Self {
+            error: Mutex::new(error.into()),
+            input: Mutex::new(input.into()),
+            output: Mutex::new(output.into()),
+        }
+    }
+
+    /// Returns the contents of the global output stream as a lossy string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global output stream is not an in-memory buffer, or is currently locked.
+    pub fn output_string(&self) -> String {
+        contents_string(&self.output, "global output")
+    }
+
+    /// Creates a new instance using the standard streams provided by [`tokio`].
+    ///
+    /// The standard streams must be used from within a [`tokio`] runtime.
+    ///
+    /// ```
+    /// use carli::io::AsyncStreams;
+    ///
+    /// # fn main() {
+    /// let streams = AsyncStreams::standard();
+    /// # }
+    /// ```
+    pub fn standard() -> Self {
+        Self::new(tokio::io::stderr(), tokio::io::stdin(), tokio::io::stdout())
+    }
+}
+
+impl AsyncShared for AsyncStreams {
+    fn error(&self) -> &Mutex<AsyncStream> {
+        &self.error
+    }
+
+    fn input(&self) -> &Mutex<AsyncStream> {
+        &self.input
+    }
+
+    fn output(&self) -> &Mutex<AsyncStream> {
+        &self.output
+    }
+}
+
+/// Returns the contents of an in-memory stream as a lossy string.
+fn contents_string(stream: &Mutex<AsyncStream>, name: &str) -> String {
+    let stream = stream
+        .try_lock()
+        .unwrap_or_else(|_| panic!("The {} stream is currently locked.", name));
+
+    let contents = stream
+        .contents()
+        .unwrap_or_else(|| panic!("The {} stream is not an in-memory buffer.", name));
+
+    String::from_utf8_lossy(contents).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Runs the future to completion using a single threaded runtime.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn async_stream_blocking() {
+        block_on(async {
+            let mut stream: AsyncStream = b"test".to_vec().into();
+
+            assert_eq!(stream.to_string().await.unwrap(), "test");
+
+            stream.write_all(b"written").await.unwrap();
+            stream.flush().await.unwrap();
+
+            assert_eq!(stream.contents(), Some(&b"testwritten"[..]));
+        });
+    }
+
+    #[test]
+    fn async_stream_file() {
+        let path = std::env::temp_dir().join(format!("carli-{}-async", std::process::id()));
+
+        block_on(async {
+            let mut stream: AsyncStream = tokio::fs::File::create(&path).await.unwrap().into();
+
+            stream.write_all(b"test").await.unwrap();
+            stream.shutdown().await.unwrap();
+
+            let mut stream: AsyncStream = tokio::fs::File::open(&path).await.unwrap().into();
+
+            assert_eq!(stream.to_string().await.unwrap(), "test");
+            assert_eq!(stream.contents(), None);
+        });
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn async_stream_unsupported() {
+        block_on(async {
+            let mut stream = AsyncStream::writer(tokio::io::sink());
+            let mut buffer = Vec::new();
+
+            let error = stream.read_to_end(&mut buffer).await.unwrap_err();
+
+            assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+
+            let mut stream = AsyncStream::reader(tokio::io::empty());
+
+            let error = stream.write_all(b"test").await.unwrap_err();
+
+            assert_eq!(error.to_string(), "The stream does not support writing.");
+        });
+    }
+
+    #[test]
+    fn async_streams_memory() {
+        let streams = AsyncStreams::memory_with_input("test");
+
+        block_on(async {
+            let input = streams.input().lock().await.to_string().await.unwrap();
+
+            let mut output = streams.output().lock().await;
+
+            output.write_all(input.as_bytes()).await.unwrap();
+
+            streams
+                .error()
+                .lock()
+                .await
+                .write_all(b"error")
+                .await
+                .unwrap();
+        });
+
+        assert_eq!(streams.error_string(), "error");
+        assert_eq!(streams.output_string(), "test");
+    }
+
+    #[test]
+    fn async_stream_terminal() {
+        let mut stream: AsyncStream = Vec::new().into();
+
+        assert!(!stream.is_terminal());
+
+        stream.set_terminal(true);
+
+        assert!(stream.is_terminal());
+    }
+}
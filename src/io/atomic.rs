@@ -0,0 +1,166 @@
+//! Provides file output that replaces its target only once it has been committed.
+//!
+//! Writing directly to a file means that, if the command fails halfway through, the file is left
+//! with only part of its new contents. Instead, everything is written to a temporary file in the
+//! same directory as the target, which is renamed over the target once it has been committed.
+//! Since a rename within the same file system is atomic, the target will either have all of its
+//! old contents or all of its new contents.
+
+use std::io::{self, Write};
+use std::sync::{Mutex, MutexGuard};
+use std::{fs, path};
+
+/// The paths of the temporary files that have not been committed or removed yet.
+static PENDING: Mutex<Vec<path::PathBuf>> = Mutex::new(Vec::new());
+
+/// A temporary file that replaces its target when committed.
+///
+/// The temporary file is removed when it is dropped without having been committed.
+#[derive(Debug)]
+pub(super) struct Atomic {
+    /// The temporary file being written to.
+    file: Option<tempfile::NamedTempFile>,
+
+    /// The path of the file to replace.
+    target: path::PathBuf,
+}
+
+impl Atomic {
+    /// Renames the temporary file over the target and returns the target file.
+    ///
+    /// Everything written is flushed first. If the target already existed, its permissions were
+    /// copied to the temporary file when it was created. If the temporary file could not be
+    /// renamed, it is kept so that writing or committing can be tried again.
+    pub(super) fn commit(&mut self) -> io::Result<fs::File> {
+        self.file()?.flush()?;
+
+        let file = self.file.take().ok_or_else(committed)?;
+        let temp = file.path().to_path_buf();
+
+        match file.persist(&self.target) {
+            Ok(file) => {
+                unregister(&temp);
+
+                Ok(file)
+            }
+            Err(error) => {
+                self.file = Some(error.file);
+
+                Err(error.error)
+            }
+        }
+    }
+
+    /// Creates a temporary file in the same directory as the target.
+    pub(super) fn new(target: &path::Path) -> io::Result<Self> {
+        let directory = match target.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => path::Path::new("."),
+        };
+
+        let mut builder = tempfile::Builder::new();
+
+        builder.prefix(".carli-").suffix(".tmp");
+
+        // Match the permissions of a newly created file, instead of only allowing the owner.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            builder.permissions(fs::Permissions::from_mode(0o666));
+        }
+
+        let file = builder.tempfile_in(directory)?;
+
+        match fs::metadata(target) {
+            Ok(metadata) => file.as_file().set_permissions(metadata.permissions())?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+
+        lock().push(file.path().to_path_buf());
+
+        Ok(Self {
+            file: Some(file),
+            target: target.to_path_buf(),
+        })
+    }
+
+    /// Returns the temporary file being written to.
+    fn file(&mut self) -> io::Result<&mut fs::File> {
+        self.file
+            .as_mut()
+            .map(tempfile::NamedTempFile::as_file_mut)
+            .ok_or_else(committed)
+    }
+}
+
+impl Drop for Atomic {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            let temp = file.path().to_path_buf();
+
+            drop(file);
+            unregister(&temp);
+        }
+    }
+}
+
+impl io::Read for Atomic {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file()?.read(buf)
+    }
+}
+
+impl io::Seek for Atomic {
+    fn seek(&mut self, position: io::SeekFrom) -> io::Result<u64> {
+        self.file()?.seek(position)
+    }
+}
+
+impl io::Write for Atomic {
+    fn flush(&mut self) -> io::Result<()> {
+        self.file()?.flush()
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.file()?.write(buffer)
+    }
+}
+
+/// Removes every temporary file that has not been committed.
+///
+/// The temporary files are normally removed when their [`super::Stream`] is dropped without having
+/// been committed. However, [`std::process::exit`] does not run destructors, so this function
+/// should be called before the process exits early. [`crate::error::Error::exit`] already does
+/// this.
+///
+/// ```no_run
+/// use carli::io;
+///
+/// # fn main() {
+/// io::discard_uncommitted();
+///
+/// std::process::exit(1);
+/// # }
+/// ```
+pub fn discard_uncommitted() {
+    for temp in lock().drain(..) {
+        let _ = fs::remove_file(temp);
+    }
+}
+
+/// Creates the error used when the temporary file has already been committed.
+fn committed() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "The file has already been committed.")
+}
+
+/// Acquires the lock for the pending paths, even if another thread panicked while holding it.
+fn lock() -> MutexGuard<'static, Vec<path::PathBuf>> {
+    PENDING.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Removes the path of a temporary file from the pending paths.
+fn unregister(temp: &path::Path) {
+    lock().retain(|pending| pending != temp);
+}
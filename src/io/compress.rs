@@ -0,0 +1,421 @@
+//! Provides compression and decompression of streams.
+//!
+//! Each compression format is supported behind its own cargo feature (`gzip`, `xz`, and `zstd`),
+//! so that applications only depend on the libraries for the formats that they need. Compressed
+//! input is recognized by the magic bytes at the start of the stream, while the format used for
+//! compressed output is chosen using the extension of a path or by name (e.g. from a flag).
+
+use super::Stream;
+use crate::error::{self, Error};
+use std::{fmt, io, path, str};
+
+/// The compression formats that are supported.
+///
+/// ```
+/// use carli::io::Compression;
+///
+/// # fn main() {
+/// # #[cfg(feature = "gzip")]
+/// assert_eq!(Compression::from_path("data.json.gz"), Some(Compression::Gzip));
+/// assert_eq!(Compression::from_path("data.json"), None);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    /// The gzip format, as used by `.gz` files.
+    #[cfg(feature = "gzip")]
+    Gzip,
+
+    /// The xz format, as used by `.xz` files.
+    #[cfg(feature = "xz")]
+    Xz,
+
+    /// The Zstandard format, as used by `.zst` files.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the compression format for the extension of the path, if it is supported.
+    ///
+    /// ```
+    /// use carli::io::Compression;
+    ///
+    /// # fn main() {
+    /// # #[cfg(feature = "zstd")]
+    /// assert_eq!(Compression::from_path("data.zst"), Some(Compression::Zstd));
+    /// # }
+    /// ```
+    pub fn from_path<P: AsRef<path::Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?;
+
+        Self::all()
+            .iter()
+            .copied()
+            .find(|compression| compression.extension().eq_ignore_ascii_case(extension))
+    }
+
+    /// Returns the compression formats that are supported.
+    fn all() -> &'static [Self] {
+        &[
+            #[cfg(feature = "gzip")]
+            Self::Gzip,
+            #[cfg(feature = "xz")]
+            Self::Xz,
+            #[cfg(feature = "zstd")]
+            Self::Zstd,
+        ]
+    }
+
+    /// Returns the compression format identified by the magic bytes, if it is supported.
+    pub(super) fn detect(magic: &[u8]) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|compression| magic.starts_with(compression.magic()))
+    }
+
+    /// Returns the file extension used by the format.
+    fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => "gz",
+            #[cfg(feature = "xz")]
+            Self::Xz => "xz",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zst",
+        }
+    }
+
+    /// Returns the magic bytes at the start of data in the format.
+    fn magic(self) -> &'static [u8] {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => &[0x1f, 0x8b],
+            #[cfg(feature = "xz")]
+            Self::Xz => &[0xfd, b'7', b'z', b'X', b'Z', 0x00],
+            #[cfg(feature = "zstd")]
+            Self::Zstd => &[0x28, 0xb5, 0x2f, 0xfd],
+        }
+    }
+
+    /// Returns the name of the format.
+    fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => "gzip",
+            #[cfg(feature = "xz")]
+            Self::Xz => "xz",
+            #[cfg(feature = "zstd")]
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl str::FromStr for Compression {
+    type Err = Error;
+
+    /// Parses the name or file extension of a compression format (e.g. `gzip` or `gz`).
+    fn from_str(name: &str) -> error::Result<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|compression| {
+                compression.name().eq_ignore_ascii_case(name)
+                    || compression.extension().eq_ignore_ascii_case(name)
+            })
+            .ok_or_else(|| Error::new(1).message(format!("Unsupported compression: {}", name)))
+    }
+}
+
+/// A writer that compresses everything before writing it to a stream.
+pub(super) enum Encoder {
+    /// Compresses using the gzip format.
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<Stream>),
+
+    /// Compresses using the xz format.
+    #[cfg(feature = "xz")]
+    Xz(xz2::write::XzEncoder<Stream>),
+
+    /// Compresses using the Zstandard format.
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, Stream>),
+}
+
+impl fmt::Debug for Encoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Encoder").field(&self.compression()).finish()
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+impl Encoder {
+    /// Writes the end of the compressed data, after which nothing else can be written.
+    pub(super) fn finish(&mut self) -> io::Result<()> {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut encoder) => encoder.try_finish(),
+            #[cfg(feature = "xz")]
+            Self::Xz(ref mut encoder) => encoder.try_finish(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut encoder) => encoder.do_finish(),
+        }
+    }
+
+    /// Returns the stream that the compressed data is written to.
+    pub(super) fn inner(&self) -> &Stream {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref encoder) => encoder.get_ref(),
+            #[cfg(feature = "xz")]
+            Self::Xz(ref encoder) => encoder.get_ref(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref encoder) => encoder.get_ref(),
+        }
+    }
+
+    /// Returns the stream that the compressed data is written to, mutably.
+    pub(super) fn inner_mut(&mut self) -> &mut Stream {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut encoder) => encoder.get_mut(),
+            #[cfg(feature = "xz")]
+            Self::Xz(ref mut encoder) => encoder.get_mut(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut encoder) => encoder.get_mut(),
+        }
+    }
+
+    /// Creates a writer that compresses using the format.
+    pub(super) fn new(stream: Stream, compression: Compression) -> io::Result<Self> {
+        Ok(match compression {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Self::Gzip(flate2::write::GzEncoder::new(
+                stream,
+                flate2::Compression::default(),
+            )),
+            #[cfg(feature = "xz")]
+            Compression::Xz => Self::Xz(xz2::write::XzEncoder::new(stream, 6)),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(stream, 0)?),
+        })
+    }
+
+    /// Returns the compression format used.
+    fn compression(&self) -> Compression {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(_) => Compression::Gzip,
+            #[cfg(feature = "xz")]
+            Self::Xz(_) => Compression::Xz,
+            #[cfg(feature = "zstd")]
+            Self::Zstd(_) => Compression::Zstd,
+        }
+    }
+}
+
+impl io::Write for Encoder {
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut encoder) => encoder.flush(),
+            #[cfg(feature = "xz")]
+            Self::Xz(ref mut encoder) => encoder.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut encoder) => encoder.flush(),
+        }
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        match *self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut encoder) => encoder.write(buffer),
+            #[cfg(feature = "xz")]
+            Self::Xz(ref mut encoder) => encoder.write(buffer),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut encoder) => encoder.write(buffer),
+        }
+    }
+}
+
+/// Creates a reader that decompresses a stream using the format.
+///
+/// Data that consists of several compressed members joined together (e.g. `cat a.gz b.gz`) is
+/// decompressed as a whole.
+pub(super) fn decoder(
+    stream: impl io::Read + Send + 'static,
+    compression: Compression,
+) -> io::Result<Box<dyn io::Read + Send>> {
+    Ok(match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(stream)),
+        #[cfg(feature = "xz")]
+        Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(stream)),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(stream)?),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    /// Compresses the data using the format, and then decompresses it using detection.
+    fn round_trip(compression: Compression, data: &[u8]) {
+        let mut output: Stream = Vec::new().into();
+
+        output.compress(compression).unwrap();
+        output.write_all(data).unwrap();
+        output.commit().unwrap();
+
+        let compressed = output.contents().unwrap().to_vec();
+
+        assert_eq!(Compression::detect(&compressed), Some(compression));
+
+        let mut input: Stream = compressed.into();
+
+        input.decompress().unwrap();
+
+        let mut lines = input.lines();
+
+        assert_eq!(lines.read_line().unwrap().unwrap(), "first");
+        assert_eq!(lines.read_line().unwrap().unwrap(), "second");
+        assert!(lines.read_line().is_none());
+    }
+
+    /// Compresses the data using the format, and then writes more data after committing.
+    fn write_after_commit(compression: Compression) {
+        let mut output: Stream = Vec::new().into();
+
+        output.compress(compression).unwrap();
+        output.write_all(b"first\nsecond\n").unwrap();
+        output.commit().unwrap();
+        output.write_all(b"plain").unwrap();
+        output.commit().unwrap();
+
+        let contents = output.contents().unwrap();
+
+        assert_eq!(Compression::detect(contents), Some(compression));
+        assert!(contents.ends_with(b"plain"));
+    }
+
+    #[test]
+    fn compress_keeps_settings() {
+        let mut output: Stream = Vec::new().into();
+
+        output.set_color(crate::style::ColorChoice::Always);
+        output.set_read_limit(Some(4));
+        output.set_terminal(true);
+        output.compress(Compression::all()[0]).unwrap();
+
+        assert_eq!(output.color(), crate::style::ColorChoice::Always);
+        assert_eq!(output.limit, Some(4));
+        assert!(output.is_terminal());
+
+        let mut input: Stream = b"test".to_vec().into();
+
+        input.set_color(crate::style::ColorChoice::Always);
+        input.set_read_limit(Some(4));
+        input.set_terminal(true);
+        input.decompress().unwrap();
+
+        assert_eq!(input.color(), crate::style::ColorChoice::Always);
+        assert_eq!(input.limit, Some(4));
+        assert!(input.is_terminal());
+    }
+
+    #[test]
+    fn compression_from_str() {
+        for compression in Compression::all() {
+            assert_eq!(
+                compression.name().parse::<Compression>().unwrap(),
+                *compression
+            );
+            assert_eq!(
+                compression.extension().parse::<Compression>().unwrap(),
+                *compression
+            );
+        }
+
+        let error = "test".parse::<Compression>().unwrap_err();
+
+        assert_eq!(error.to_string(), "Unsupported compression: test\n");
+    }
+
+    #[test]
+    fn decompress_uncompressed() {
+        let mut stream = Stream::reader(io::Cursor::new(b"test".to_vec()));
+
+        stream.decompress().unwrap();
+
+        assert_eq!(stream.to_string().unwrap(), "test");
+
+        let mut stream: Stream = b"t".to_vec().into();
+
+        stream.decompress().unwrap();
+
+        assert_eq!(stream.contents(), Some(&b"t"[..]));
+        assert_eq!(stream.to_string().unwrap(), "t");
+    }
+
+    #[test]
+    fn decompress_uncompressed_write() {
+        let mut stream: Stream = b"test".to_vec().into();
+
+        stream.decompress().unwrap();
+
+        write!(stream, "ing").unwrap();
+
+        assert_eq!(stream.contents(), Some(&b"testing"[..]));
+        assert_eq!(stream.to_string().unwrap(), "testing");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn round_trip_gzip() {
+        round_trip(Compression::Gzip, b"first\nsecond\n");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn write_after_commit_gzip() {
+        write_after_commit(Compression::Gzip);
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn round_trip_xz() {
+        round_trip(Compression::Xz, b"first\nsecond\n");
+    }
+
+    #[cfg(feature = "xz")]
+    #[test]
+    fn write_after_commit_xz() {
+        write_after_commit(Compression::Xz);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn round_trip_zstd() {
+        round_trip(Compression::Zstd, b"first\nsecond\n");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn write_after_commit_zstd() {
+        write_after_commit(Compression::Zstd);
+    }
+}
@@ -0,0 +1,336 @@
+//! Provides reading of streams as text in encodings other than UTF-8.
+//!
+//! The encoding of a stream is either given explicitly (e.g. from a flag), or detected using the
+//! byte order mark (BOM) at the start of the stream, with UTF-8 being assumed if there is none.
+//! Since many files created on Windows use `\r\n` for line endings, these can be normalized to
+//! `\n` while the text is decoded.
+
+use crate::error::{self, Error};
+use std::{fmt, str};
+
+/// The text encodings that are supported.
+///
+/// ```
+/// use carli::io::Encoding;
+///
+/// # fn main() {
+/// assert_eq!("utf-16le".parse::<Encoding>().unwrap(), Encoding::Utf16Le);
+/// assert_eq!(Encoding::Latin1.to_string(), "latin1");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Encoding {
+    /// The ISO-8859-1 encoding, where every byte is a character.
+    Latin1,
+
+    /// The UTF-16 encoding, using big-endian code units.
+    Utf16Be,
+
+    /// The UTF-16 encoding, using little-endian code units.
+    Utf16Le,
+
+    /// The UTF-8 encoding.
+    Utf8,
+}
+
+impl Encoding {
+    /// Returns the encoding identified by the byte order mark at the start of the bytes, if any.
+    ///
+    /// ```
+    /// use carli::io::Encoding;
+    ///
+    /// # fn main() {
+    /// assert_eq!(Encoding::detect(b"\xff\xfeh\x00i\x00"), Some(Encoding::Utf16Le));
+    /// assert_eq!(Encoding::detect(b"hi"), None);
+    /// # }
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        [Self::Utf8, Self::Utf16Be, Self::Utf16Le]
+            .into_iter()
+            .find(|encoding| bytes.starts_with(encoding.bom()))
+    }
+
+    /// Returns the byte order mark for the encoding, which is empty if it does not have one.
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Self::Latin1 => &[],
+            Self::Utf16Be => &[0xfe, 0xff],
+            Self::Utf16Le => &[0xff, 0xfe],
+            Self::Utf8 => &[0xef, 0xbb, 0xbf],
+        }
+    }
+
+    /// Decodes the bytes into a string, skipping the byte order mark if there is one.
+    fn decode(self, bytes: &[u8]) -> error::Result<String> {
+        let start = if bytes.starts_with(self.bom()) {
+            self.bom().len()
+        } else {
+            0
+        };
+
+        let bytes = &bytes[start..];
+
+        match self {
+            Self::Latin1 => Ok(bytes.iter().copied().map(char::from).collect()),
+            Self::Utf16Be => self.decode_utf16(bytes, start, u16::from_be_bytes),
+            Self::Utf16Le => self.decode_utf16(bytes, start, u16::from_le_bytes),
+            Self::Utf8 => str::from_utf8(bytes)
+                .map(str::to_owned)
+                .map_err(|error| self.invalid(start + error.valid_up_to())),
+        }
+    }
+
+    /// Decodes UTF-16 code units that are read from pairs of bytes using the function.
+    fn decode_utf16(
+        self,
+        bytes: &[u8],
+        start: usize,
+        unit: fn([u8; 2]) -> u16,
+    ) -> error::Result<String> {
+        let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+        let mut offset = start;
+        let mut string = String::with_capacity(bytes.len() / 2);
+
+        for result in char::decode_utf16(units) {
+            let character = result.map_err(|_| self.invalid(offset))?;
+
+            offset += character.len_utf16() * 2;
+
+            string.push(character);
+        }
+
+        if bytes.len() % 2 != 0 {
+            return Err(self.invalid(offset));
+        }
+
+        Ok(string)
+    }
+
+    /// Creates the error for bytes that could not be decoded at the offset.
+    fn invalid(self, offset: usize) -> Error {
+        Error::new(1).message(format!(
+            "The stream is not valid {} at byte offset {}.",
+            self.label(),
+            offset
+        ))
+    }
+
+    /// Returns the name of the encoding as it is commonly written.
+    fn label(self) -> &'static str {
+        match self {
+            Self::Latin1 => "Latin-1",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf8 => "UTF-8",
+        }
+    }
+
+    /// Returns the name of the encoding.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Latin1 => "latin1",
+            Self::Utf16Be => "utf-16be",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf8 => "utf-8",
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl str::FromStr for Encoding {
+    type Err = Error;
+
+    /// Parses the name of an encoding (e.g. `utf-8`, `utf16le`, or `iso-8859-1`).
+    fn from_str(name: &str) -> error::Result<Self> {
+        let normalized: String = name
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        match normalized.as_str() {
+            "latin1" | "iso88591" => Ok(Self::Latin1),
+            "utf16be" => Ok(Self::Utf16Be),
+            "utf16le" => Ok(Self::Utf16Le),
+            "utf8" => Ok(Self::Utf8),
+            _ => Err(Error::new(1).message(format!("Unsupported encoding: {}", name))),
+        }
+    }
+}
+
+/// The options used to read a stream as text.
+///
+/// This type is used with [`super::Stream::to_text`]. By default, the encoding is detected using
+/// the byte order mark, and line endings are left as they are.
+///
+/// ```
+/// use carli::io::{Encoding, Text};
+///
+/// # fn main() {
+/// let text = Text::new()
+///     .encoding(Encoding::Latin1)
+///     .normalize_newlines(true);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Text {
+    /// The encoding to use instead of detecting it.
+    encoding: Option<Encoding>,
+
+    /// The flag used to replace `\r\n` with `\n`.
+    normalize: bool,
+}
+
+impl Text {
+    /// Sets the encoding to use instead of detecting it.
+    ///
+    /// A byte order mark at the start of the stream is still skipped if it is for the encoding.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = Some(encoding);
+
+        self
+    }
+
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether `\r\n` line endings are replaced with `\n`.
+    pub fn normalize_newlines(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+
+        self
+    }
+
+    /// Decodes the bytes into a string using the options.
+    pub(super) fn decode(&self, bytes: &[u8]) -> error::Result<String> {
+        let encoding = self
+            .encoding
+            .or_else(|| Encoding::detect(bytes))
+            .unwrap_or(Encoding::Utf8);
+
+        let string = encoding.decode(bytes)?;
+
+        if self.normalize && string.contains("\r\n") {
+            Ok(string.replace("\r\n", "\n"))
+        } else {
+            Ok(string)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Inspect;
+    use crate::io::Stream;
+
+    #[test]
+    fn encoding_from_str() {
+        for encoding in [
+            Encoding::Latin1,
+            Encoding::Utf16Be,
+            Encoding::Utf16Le,
+            Encoding::Utf8,
+        ] {
+            assert_eq!(encoding.name().parse::<Encoding>().unwrap(), encoding);
+        }
+
+        assert_eq!("ISO-8859-1".parse::<Encoding>().unwrap(), Encoding::Latin1);
+        assert_eq!("UTF16LE".parse::<Encoding>().unwrap(), Encoding::Utf16Le);
+
+        let error = "test".parse::<Encoding>().unwrap_err();
+
+        assert_eq!(error.to_string(), "Unsupported encoding: test\n");
+    }
+
+    #[test]
+    fn to_text_detected() {
+        let mut stream: Stream = b"\xef\xbb\xbfcaf\xc3\xa9".to_vec().into();
+
+        assert_eq!(stream.to_text(Text::new()).unwrap(), "café");
+
+        let mut stream: Stream = b"\xff\xfec\x00a\x00f\x00\xe9\x00".to_vec().into();
+
+        assert_eq!(stream.to_text(Text::new()).unwrap(), "café");
+
+        let mut stream: Stream = b"\xfe\xff\x00c\x00a\x00f\x00\xe9".to_vec().into();
+
+        assert_eq!(stream.to_text(Text::new()).unwrap(), "café");
+
+        let mut stream: Stream = b"caf\xc3\xa9".to_vec().into();
+
+        assert_eq!(stream.to_text(Text::new()).unwrap(), "café");
+    }
+
+    #[test]
+    fn to_text_explicit() {
+        let mut stream: Stream = b"caf\xe9".to_vec().into();
+
+        assert_eq!(
+            stream
+                .to_text(Text::new().encoding(Encoding::Latin1))
+                .unwrap(),
+            "café"
+        );
+
+        let mut stream: Stream = b"h\x00i\x00".to_vec().into();
+
+        assert_eq!(
+            stream
+                .to_text(Text::new().encoding(Encoding::Utf16Le))
+                .unwrap(),
+            "hi"
+        );
+    }
+
+    #[test]
+    fn to_text_invalid() {
+        let mut stream: Stream = b"\xef\xbb\xbfab\xffcd".to_vec().into();
+        let error = stream.to_text(Text::new()).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("The stream is not valid UTF-8 at byte offset 5.")
+        );
+        assert_eq!(error.get_status(), 1);
+
+        let mut stream: Stream = b"\xff\xfea\x00\x00\xdcb\x00".to_vec().into();
+        let error = stream.to_text(Text::new()).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("The stream is not valid UTF-16LE at byte offset 4.")
+        );
+
+        let mut stream: Stream = b"\xfe\xff\x00a\x00".to_vec().into();
+        let error = stream.to_text(Text::new()).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("The stream is not valid UTF-16BE at byte offset 4.")
+        );
+    }
+
+    #[test]
+    fn to_text_normalized() {
+        let mut stream: Stream = b"a\r\nb\r\n".to_vec().into();
+
+        assert_eq!(stream.to_text(Text::new()).unwrap(), "a\r\nb\r\n");
+
+        let mut stream: Stream = b"\xff\xfea\x00\r\x00\n\x00b\x00".to_vec().into();
+
+        assert_eq!(
+            stream
+                .to_text(Text::new().normalize_newlines(true))
+                .unwrap(),
+            "a\nb"
+        );
+    }
+}
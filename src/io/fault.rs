@@ -0,0 +1,409 @@
+//! Provides a stream that fails on command, for testing how errors are handled.
+//!
+//! In-memory buffers never fail, which leaves the error handling of a command untested. A
+//! [`Faulty`] stream wraps another stream and follows a script of failures, such as a broken pipe
+//! on the third write or a full disk after a number of bytes, while passing everything else
+//! through to the stream it wraps.
+
+use super::{Stream, StreamKind};
+use std::io;
+
+/// A stream that fails according to a script, for testing how errors are handled.
+///
+/// Reads and writes are counted starting from one, including those that fail. Failures set with
+/// [`Faulty::fail_read`], [`Faulty::fail_write`], and [`Faulty::limit_writes`] are permanent, in
+/// the same way that a closed pipe or a full disk remains that way. Failures set with
+/// [`Faulty::transient_read`] and [`Faulty::transient_write`] only happen once, which is how
+/// [`io::ErrorKind::Interrupted`] and [`io::ErrorKind::WouldBlock`] are expected to behave.
+///
+/// ```
+/// use carli::error::{Error, Inspect};
+/// use carli::io::{Faulty, Shared, Streams};
+/// use std::io::{self, Write};
+///
+/// # fn main() {
+/// let streams = Streams::builder()
+///     .output(Faulty::new(Vec::new()).fail_write(2, io::Error::from_raw_os_error(32)))
+///     .build();
+///
+/// writeln!(streams.output(), "first").unwrap();
+///
+/// let error = Error::from(writeln!(streams.output(), "second").unwrap_err());
+///
+/// assert_eq!(error.get_status(), 32);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Faulty {
+    /// The error returned when flushing.
+    flush: Option<io::Error>,
+
+    /// The stream that is passed through to.
+    inner: Stream,
+
+    /// The number of bytes that can be written, and the error returned once they have been.
+    limit: Option<(usize, io::Error)>,
+
+    /// The read from which every read fails, and the error returned.
+    read_failure: Option<(usize, io::Error)>,
+
+    /// The reads that fail once, and the kinds of error returned.
+    read_transients: Vec<(usize, io::ErrorKind)>,
+
+    /// The number of reads attempted so far.
+    reads: usize,
+
+    /// The write from which every write fails, and the error returned.
+    write_failure: Option<(usize, io::Error)>,
+
+    /// The writes that fail once, and the kinds of error returned.
+    write_transients: Vec<(usize, io::ErrorKind)>,
+
+    /// The number of writes attempted so far.
+    writes: usize,
+
+    /// The number of bytes written so far.
+    written: usize,
+}
+
+impl From<Faulty> for Stream {
+    fn from(faulty: Faulty) -> Self {
+        Self::new(StreamKind::Faulty(Box::new(faulty)))
+    }
+}
+
+impl Faulty {
+    /// Makes every flush fail with the error.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(Vec::new())
+    ///     .fail_flush(io::ErrorKind::BrokenPipe.into())
+    ///     .into();
+    ///
+    /// assert!(stream.flush().is_err());
+    /// # }
+    /// ```
+    pub fn fail_flush(mut self, error: io::Error) -> Self {
+        self.flush = Some(error);
+
+        self
+    }
+
+    /// Makes the Nth read, and every read after it, fail with the error.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Read};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(b"example".to_vec())
+    ///     .fail_read(1, io::Error::from_raw_os_error(5))
+    ///     .into();
+    ///
+    /// assert!(stream.read(&mut [0; 8]).is_err());
+    /// # }
+    /// ```
+    pub fn fail_read(mut self, nth: usize, error: io::Error) -> Self {
+        self.read_failure = Some((nth, error));
+
+        self
+    }
+
+    /// Makes the Nth write, and every write after it, fail with the error.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(Vec::new())
+    ///     .fail_write(2, io::Error::from_raw_os_error(32))
+    ///     .into();
+    ///
+    /// assert!(write!(stream, "first").is_ok());
+    /// assert!(write!(stream, "second").is_err());
+    /// # }
+    /// ```
+    pub fn fail_write(mut self, nth: usize, error: io::Error) -> Self {
+        self.write_failure = Some((nth, error));
+
+        self
+    }
+
+    /// Makes writes fail with the error once the number of bytes have been written.
+    ///
+    /// A write that would go past the limit is cut short, which means that short writes can be
+    /// tested as well. Only once the limit has been reached are errors returned.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(Vec::new())
+    ///     .limit_writes(4, io::Error::from_raw_os_error(28))
+    ///     .into();
+    ///
+    /// assert_eq!(stream.write(b"example").unwrap(), 4);
+    /// assert!(stream.write(b"ple").is_err());
+    /// assert_eq!(stream.contents(), Some(&b"exam"[..]));
+    /// # }
+    /// ```
+    pub fn limit_writes(mut self, bytes: usize, error: io::Error) -> Self {
+        self.limit = Some((bytes, error));
+
+        self
+    }
+
+    /// Creates a stream that passes everything through to another stream until told to fail.
+    ///
+    /// ```
+    /// use carli::io::Faulty;
+    ///
+    /// # fn main() {
+    /// let faulty = Faulty::new(b"example".to_vec());
+    /// # }
+    /// ```
+    pub fn new<S: Into<Stream>>(inner: S) -> Self {
+        Self {
+            flush: None,
+            inner: inner.into(),
+            limit: None,
+            read_failure: None,
+            read_transients: Vec::new(),
+            reads: 0,
+            write_failure: None,
+            write_transients: Vec::new(),
+            writes: 0,
+            written: 0,
+        }
+    }
+
+    /// Makes only the Nth read fail with an error of the kind.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Read};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(b"example".to_vec())
+    ///     .transient_read(1, io::ErrorKind::WouldBlock)
+    ///     .into();
+    ///
+    /// let mut buffer = [0; 8];
+    ///
+    /// assert!(stream.read(&mut buffer).is_err());
+    /// assert_eq!(stream.read(&mut buffer).unwrap(), 7);
+    /// # }
+    /// ```
+    pub fn transient_read(mut self, nth: usize, kind: io::ErrorKind) -> Self {
+        self.read_transients.push((nth, kind));
+
+        self
+    }
+
+    /// Makes only the Nth write fail with an error of the kind.
+    ///
+    /// ```
+    /// use carli::io::{Faulty, Stream};
+    /// use std::io::{self, Write};
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Faulty::new(Vec::new())
+    ///     .transient_write(1, io::ErrorKind::Interrupted)
+    ///     .into();
+    ///
+    /// // Interrupted writes are retried by write_all().
+    /// stream.write_all(b"example").unwrap();
+    ///
+    /// assert_eq!(stream.contents(), Some(&b"example"[..]));
+    /// # }
+    /// ```
+    pub fn transient_write(mut self, nth: usize, kind: io::ErrorKind) -> Self {
+        self.write_transients.push((nth, kind));
+
+        self
+    }
+
+    /// Returns the stream that is passed through to.
+    pub(super) fn inner(&self) -> &Stream {
+        &self.inner
+    }
+
+    /// Returns the stream that is passed through to, mutably.
+    pub(super) fn inner_mut(&mut self) -> &mut Stream {
+        &mut self.inner
+    }
+}
+
+impl io::Read for Faulty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads += 1;
+
+        check(self.reads, &self.read_transients, &self.read_failure)?;
+
+        self.inner.read(buf)
+    }
+}
+
+impl io::Seek for Faulty {
+    fn seek(&mut self, position: io::SeekFrom) -> io::Result<u64> {
+        self.inner.seek(position)
+    }
+}
+
+impl io::Write for Faulty {
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(error) = &self.flush {
+            return Err(copy(error));
+        }
+
+        self.inner.flush()
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+
+        check(self.writes, &self.write_transients, &self.write_failure)?;
+
+        let mut length = buffer.len();
+
+        if let Some((bytes, error)) = &self.limit {
+            let remaining = bytes.saturating_sub(self.written);
+
+            if remaining == 0 && !buffer.is_empty() {
+                return Err(copy(error));
+            }
+
+            length = length.min(remaining);
+        }
+
+        let written = self.inner.write(&buffer[..length])?;
+
+        self.written += written;
+
+        Ok(written)
+    }
+}
+
+/// Returns the error for the attempt, if it is scripted to fail.
+fn check(
+    attempt: usize,
+    transients: &[(usize, io::ErrorKind)],
+    failure: &Option<(usize, io::Error)>,
+) -> io::Result<()> {
+    if let Some((_, kind)) = transients.iter().find(|(nth, _)| *nth == attempt) {
+        return Err(io::Error::new(*kind, "The stream failed as scripted."));
+    }
+
+    match failure {
+        Some((nth, error)) if attempt >= *nth => Err(copy(error)),
+        _ => Ok(()),
+    }
+}
+
+/// Creates a copy of the error so that it can be returned more than once.
+fn copy(error: &io::Error) -> io::Error {
+    match error.raw_os_error() {
+        Some(code) => io::Error::from_raw_os_error(code),
+        None => io::Error::new(error.kind(), error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::{Error, Inspect};
+    use std::io::{Read, Write};
+
+    #[test]
+    fn fail_flush() {
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .fail_flush(io::ErrorKind::Other.into())
+            .into();
+
+        write!(stream, "test").unwrap();
+
+        assert_eq!(stream.flush().unwrap_err().kind(), io::ErrorKind::Other);
+        assert_eq!(stream.contents(), Some(&b"test"[..]));
+    }
+
+    #[test]
+    fn fail_read() {
+        let mut stream: Stream = Faulty::new(b"test".to_vec())
+            .fail_read(2, io::Error::from_raw_os_error(5))
+            .into();
+
+        let mut buffer = [0; 2];
+
+        stream.read_exact(&mut buffer).unwrap();
+
+        for _ in 0..2 {
+            let error = Error::from(stream.read(&mut buffer).unwrap_err());
+
+            assert_eq!(error.get_status(), 5);
+        }
+    }
+
+    #[test]
+    fn fail_write() {
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .fail_write(2, io::Error::from_raw_os_error(32))
+            .into();
+
+        write!(stream, "first").unwrap();
+
+        for _ in 0..2 {
+            let error = Error::from(write!(stream, "second").unwrap_err());
+
+            assert_eq!(error.get_status(), 32);
+        }
+
+        assert_eq!(stream.contents(), Some(&b"first"[..]));
+    }
+
+    #[test]
+    fn limit_writes() {
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .limit_writes(6, io::Error::from_raw_os_error(28))
+            .into();
+
+        assert_eq!(stream.write(b"test").unwrap(), 4);
+        assert_eq!(stream.write(b"test").unwrap(), 2);
+
+        let error = Error::from(stream.write_all(b"test").unwrap_err());
+
+        assert_eq!(error.get_status(), 28);
+        assert_eq!(stream.contents(), Some(&b"testte"[..]));
+    }
+
+    #[test]
+    fn transient_read() {
+        let mut stream: Stream = Faulty::new(b"test".to_vec())
+            .transient_read(1, io::ErrorKind::Interrupted)
+            .into();
+
+        assert_eq!(stream.to_string().unwrap(), "test");
+    }
+
+    #[test]
+    fn transient_write() {
+        let mut stream: Stream = Faulty::new(Vec::new())
+            .transient_write(2, io::ErrorKind::WouldBlock)
+            .into();
+
+        write!(stream, "first").unwrap();
+
+        let error = write!(stream, "second").unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+
+        write!(stream, "third").unwrap();
+
+        assert_eq!(stream.contents(), Some(&b"firstthird"[..]));
+    }
+}
@@ -0,0 +1,266 @@
+//! Provides streams that can be shared between threads.
+//!
+//! The streams managed by [`Streams`] are kept in [`cell::RefCell`], which is cheap but cannot be
+//! shared between threads. When a command needs to write from several threads, the streams are
+//! moved behind a [`sync::Mutex`] using [`Streams::sync`], and the original context keeps working
+//! by locking the same streams as needed.
+
+use super::{Stream, StreamKind, Streams};
+use std::{cell, sync};
+
+/// A trait for objects that manage input and output streams that can be shared between threads.
+///
+/// This is the counterpart to [`super::Shared`] for contexts that are used by several threads at
+/// once. Each stream is locked for as long as the guard that is returned is alive, so everything
+/// written using the same guard (e.g. a whole line written using [`writeln!`]) is kept together.
+///
+/// ```
+/// use carli::error::Result;
+/// use carli::io::SyncShared;
+/// use std::io::Write;
+///
+/// fn example(streams: &dyn SyncShared, name: &str) -> Result<()> {
+///     writeln!(streams.output(), "Processed: {}", name)?;
+///
+///     Ok(())
+/// }
+/// ```
+pub trait SyncShared: Send + Sync {
+    /// Locks and returns the error output stream.
+    fn error(&self) -> sync::MutexGuard<'_, Stream>;
+
+    /// Locks and returns the input stream.
+    fn input(&self) -> sync::MutexGuard<'_, Stream>;
+
+    /// Locks and returns the global output stream.
+    fn output(&self) -> sync::MutexGuard<'_, Stream>;
+}
+
+/// Manages a collection of input and output streams that can be shared between threads.
+///
+/// An instance is created from an existing context using [`Streams::sync`], which means that
+/// commands continue to use [`Streams`] with [`crate::command::Execute`] and
+/// [`crate::command::Main`], and only move the streams behind a lock when they start using
+/// threads. Cloning the instance is cheap, since the clones lock the same streams.
+///
+/// A stream must not be used through the original context while the same thread holds a guard
+/// returned by this type, since doing so would wait for the guard to be dropped forever.
+///
+/// ```
+/// use carli::error::Result;
+/// use carli::io::{Streams, SyncShared};
+/// use std::io::Write;
+/// use std::thread;
+///
+/// fn example(context: &Streams, paths: &[String]) -> Result<()> {
+///     let shared = context.sync();
+///
+///     thread::scope(|scope| {
+///         let workers: Vec<_> = paths
+///             .iter()
+///             .map(|path| {
+///                 let shared = shared.clone();
+///
+///                 scope.spawn(move || writeln!(shared.output(), "Processed: {}", path))
+///             })
+///             .collect();
+///
+///         workers
+///             .into_iter()
+///             .try_for_each(|worker| worker.join().expect("The worker panicked."))
+///     })?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SyncStreams {
+    /// The error output stream.
+    error: sync::Arc<sync::Mutex<Stream>>,
+
+    /// The input stream.
+    input: sync::Arc<sync::Mutex<Stream>>,
+
+    /// The global output stream.
+    output: sync::Arc<sync::Mutex<Stream>>,
+}
+
+impl SyncStreams {
+    /// Creates a new instance that shares the streams of the context.
+    pub(super) fn new(streams: &Streams) -> Self {
+        Self {
+            error: share(&streams.error),
+            input: share(&streams.input),
+            output: share(&streams.output),
+        }
+    }
+}
+
+impl SyncShared for SyncStreams {
+    fn error(&self) -> sync::MutexGuard<'_, Stream> {
+        lock(&self.error)
+    }
+
+    fn input(&self) -> sync::MutexGuard<'_, Stream> {
+        lock(&self.input)
+    }
+
+    fn output(&self) -> sync::MutexGuard<'_, Stream> {
+        lock(&self.output)
+    }
+}
+
+/// Locks the stream, even if another thread panicked while it was locked.
+pub(super) fn lock(stream: &sync::Mutex<Stream>) -> sync::MutexGuard<'_, Stream> {
+    stream.lock().unwrap_or_else(sync::PoisonError::into_inner)
+}
+
+/// Moves the stream in the cell behind a lock, and leaves a stream that uses the lock in its place.
+///
+/// If the stream has already been moved, the existing lock is returned.
+fn share(cell: &cell::RefCell<Stream>) -> sync::Arc<sync::Mutex<Stream>> {
+    let mut stream = cell.borrow_mut();
+
+    if let StreamKind::Locked(shared) = &stream.inner {
+        return shared.clone();
+    }
+
+    let mut locked = Stream::null();
+
+    locked.color = stream.color;
+    locked.limit = stream.limit;
+    locked.pipe = stream.pipe;
+    locked.terminal = stream.terminal;
+
+    let shared = sync::Arc::new(sync::Mutex::new(std::mem::replace(&mut *stream, locked)));
+
+    stream.inner = StreamKind::Locked(shared.clone());
+
+    shared
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::command::Execute;
+    use crate::error;
+    use crate::io::{self, Shared};
+    use std::io::Write;
+    use std::thread;
+
+    /// An example subcommand that writes from several threads.
+    struct Parallel {}
+
+    impl Execute<Streams> for Parallel {
+        fn execute(&self, context: &Streams) -> error::Result<()> {
+            let shared = context.sync();
+
+            thread::scope(|scope| {
+                for worker in 0..4 {
+                    let shared = &shared;
+
+                    scope.spawn(move || {
+                        for line in 0..100 {
+                            writeln!(shared.output(), "worker {} line {}", worker, line).unwrap();
+                        }
+                    });
+                }
+            });
+
+            writeln!(context.output(), "done")?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn sync_execute() {
+        let streams = io::memory();
+
+        Parallel {}.execute(&streams).unwrap();
+
+        let output = streams.output_string();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 401);
+        assert_eq!(lines.last(), Some(&"done"));
+
+        for worker in 0..4 {
+            let expected: Vec<_> = (0..100)
+                .map(|line| format!("worker {} line {}", worker, line))
+                .collect();
+
+            let actual: Vec<_> = lines
+                .iter()
+                .filter(|line| line.starts_with(&format!("worker {} ", worker)))
+                .map(|line| line.to_string())
+                .collect();
+
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn sync_shares_streams() {
+        let streams = io::memory_with_input("test");
+        let shared = streams.sync();
+
+        assert!(matches!(streams.output().inner, StreamKind::Locked(_)));
+        assert!(sync::Arc::ptr_eq(&shared.output, &streams.sync().output));
+
+        assert_eq!(shared.input().to_string().unwrap(), "test");
+
+        write!(streams.error(), "first ").unwrap();
+        write!(shared.error(), "second").unwrap();
+
+        assert_eq!(shared.error().contents(), Some(&b"first second"[..]));
+        assert_eq!(streams.error_string(), "first second");
+    }
+
+    #[test]
+    fn sync_contents() {
+        let streams = io::memory();
+        let shared = streams.sync();
+
+        write!(streams.output(), "test").unwrap();
+
+        assert_eq!(streams.output().contents(), None);
+        assert_eq!(shared.output().contents(), Some(&b"test"[..]));
+        assert_eq!(streams.output_string(), "test");
+    }
+
+    #[test]
+    fn sync_keeps_settings() {
+        let streams = io::memory();
+
+        streams.output().set_read_limit(Some(4));
+        streams.output().set_terminal(true);
+        streams.sync();
+
+        let output = streams.output();
+
+        assert!(matches!(output.inner, StreamKind::Locked(_)));
+        assert_eq!(output.limit, Some(4));
+        assert_eq!(output.terminal, Some(true));
+    }
+
+    #[test]
+    fn sync_poisoned() {
+        let streams = io::memory();
+        let shared = streams.sync();
+
+        let _ = thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let _guard = shared.output();
+
+                    panic!("The worker panicked.");
+                })
+                .join()
+        });
+
+        write!(shared.output(), "test").unwrap();
+
+        assert_eq!(streams.output_string(), "test");
+    }
+}
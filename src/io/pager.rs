@@ -0,0 +1,294 @@
+//! Provides output that is shown one screen at a time by a pager, such as `less`.
+//!
+//! A pager is another process that reads everything written to the stream and shows it on the
+//! terminal the stream was connected to. Since the process has to be waited on before the
+//! application exits, the pagers that are still running are kept track of so that they can be
+//! closed and waited on even when [`std::process::exit`] is used. Each pager has its own lock on
+//! its input, so writing to one pager never has to wait for another.
+
+use super::Stream;
+use std::ffi::OsStr;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::{env, path, process};
+
+/// The pagers that have not been waited on yet.
+static RUNNING: Mutex<Vec<Running>> = Mutex::new(Vec::new());
+
+/// The input of a pager, which is taken and dropped when the pager is closed.
+type Input = Arc<Mutex<Option<process::ChildStdin>>>;
+
+/// A pager that has not been waited on yet.
+#[derive(Debug)]
+struct Running {
+    /// The process of the pager.
+    child: process::Child,
+
+    /// The input of the pager, which must be closed before waiting on the process.
+    input: Input,
+}
+
+impl Running {
+    /// Closes the input of the pager, and waits for it to exit.
+    fn close(mut self) -> io::Result<()> {
+        drop(take(&self.input));
+
+        self.child.wait().map(|_| ())
+    }
+}
+
+/// A pager that is shown everything written to it.
+///
+/// The pager is waited on when it is dropped without having been closed.
+#[derive(Debug)]
+pub(super) struct Pager {
+    /// The process identifier of the pager.
+    id: u32,
+
+    /// The input of the pager, until it has been closed.
+    input: Input,
+
+    /// The stream that was written to before the pager was started.
+    target: Option<Stream>,
+}
+
+impl Pager {
+    /// Stops writing to the pager, and waits for it to exit.
+    ///
+    /// Nothing is done if the pager has already been waited on.
+    pub(super) fn close(&mut self) -> io::Result<()> {
+        drop(take(&self.input));
+
+        let running = {
+            let mut running = lock();
+
+            running
+                .iter()
+                .position(|running| running.child.id() == self.id)
+                .map(|index| running.swap_remove(index))
+        };
+
+        match running {
+            Some(running) => running.close(),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the stream that was written to before the pager was started.
+    pub(super) fn into_target(mut self) -> Stream {
+        self.target
+            .take()
+            .expect("The target has already been taken.")
+    }
+
+    /// Returns the stream that was written to before the pager was started.
+    pub(super) fn target(&self) -> &Stream {
+        self.target
+            .as_ref()
+            .expect("The target has already been taken.")
+    }
+
+    /// Starts the pager using the command, which shows everything using the output.
+    ///
+    /// The target is replaced with a stream that discards everything once the pager has started.
+    pub(super) fn start(
+        mut command: process::Command,
+        output: process::Stdio,
+        target: &mut Stream,
+    ) -> io::Result<Self> {
+        let mut child = command
+            .stdin(process::Stdio::piped())
+            .stdout(output)
+            .spawn()?;
+        let id = child.id();
+        let input = Arc::new(Mutex::new(child.stdin.take()));
+
+        lock().push(Running {
+            child,
+            input: input.clone(),
+        });
+
+        Ok(Self {
+            id,
+            input,
+            target: Some(std::mem::replace(target, Stream::null())),
+        })
+    }
+
+    /// Calls the function with the input of the pager.
+    ///
+    /// If the input has already been closed, the pipe is considered to be broken.
+    fn with_input<T>(
+        &self,
+        function: impl FnOnce(&mut process::ChildStdin) -> io::Result<T>,
+    ) -> io::Result<T> {
+        let mut input = self
+            .input
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match input.as_mut() {
+            Some(input) => function(input),
+            None => Err(io::ErrorKind::BrokenPipe.into()),
+        }
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+impl io::Write for Pager {
+    fn flush(&mut self) -> io::Result<()> {
+        self.with_input(|input| input.flush())
+    }
+
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.with_input(|input| input.write(buffer))
+    }
+}
+
+/// Returns the pager command to use for the value of the `PAGER` environment variable.
+///
+/// If the variable is not set, `less -FRX` is used. If it is empty or `cat`, no pager is used.
+pub(super) fn from_env(value: Option<&OsStr>) -> Option<process::Command> {
+    match value {
+        None => {
+            let mut command = process::Command::new("less");
+
+            command.arg("-FRX");
+
+            Some(command)
+        }
+        Some(value) => shell(value),
+    }
+}
+
+/// Returns the pager command that runs the command line using the shell.
+///
+/// If the command line is empty or `cat`, or if the program it runs cannot be found, no pager is
+/// used. Otherwise, the shell would start even though the pager cannot, and everything written to
+/// it would be lost.
+pub(super) fn shell(line: &OsStr) -> Option<process::Command> {
+    if let Some(line) = line.to_str() {
+        if matches!(line.trim(), "" | "cat") || !is_found(line) {
+            return None;
+        }
+    }
+
+    #[cfg(unix)]
+    let mut command = process::Command::new("sh");
+    #[cfg(unix)]
+    command.arg("-c");
+
+    #[cfg(not(unix))]
+    let mut command = process::Command::new("cmd");
+    #[cfg(not(unix))]
+    command.arg("/C");
+
+    command.arg(line);
+
+    Some(command)
+}
+
+/// Closes every pager that is still running, and waits for it to exit.
+///
+/// The pagers are normally waited on when their [`super::Stream`] is committed or dropped.
+/// However, [`std::process::exit`] does not run destructors, so this function should be called
+/// before the process exits early, otherwise the pager would be left behind with the terminal.
+/// [`crate::error::Error::exit`] already does this. Nothing more can be written to the pagers
+/// afterwards, since writing to a closed pager fails with a broken pipe.
+///
+/// ```no_run
+/// use carli::io;
+///
+/// # fn main() {
+/// io::wait_for_pagers();
+///
+/// std::process::exit(1);
+/// # }
+/// ```
+pub fn wait_for_pagers() {
+    let running = std::mem::take(&mut *lock());
+
+    for running in running {
+        let _ = running.close();
+    }
+}
+
+/// Checks if the program run by the command line can be found.
+///
+/// Variable assignments before the program are skipped. A program without a path is looked for in
+/// the directories of the `PATH` environment variable.
+fn is_found(line: &str) -> bool {
+    let program = match line.split_whitespace().find(|word| !word.contains('=')) {
+        Some(program) => path::Path::new(program),
+        None => return false,
+    };
+
+    if program.components().count() > 1 {
+        return program.is_file();
+    }
+
+    let directories = env::var_os("PATH").unwrap_or_default();
+
+    env::split_paths(&directories).any(|directory| {
+        let candidate = directory.join(program);
+
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
+/// Locks the pagers that are running, even if another thread panicked while they were locked.
+fn lock() -> MutexGuard<'static, Vec<Running>> {
+    RUNNING
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Takes the input of a pager, even if another thread panicked while it was locked.
+fn take(input: &Input) -> Option<process::ChildStdin> {
+    input
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .take()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_env_default() {
+        let command = from_env(None).unwrap();
+
+        assert_eq!(command.get_program(), "less");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), ["-FRX"]);
+    }
+
+    #[test]
+    fn from_env_disabled() {
+        assert!(from_env(Some(OsStr::new(""))).is_none());
+        assert!(from_env(Some(OsStr::new(" cat "))).is_none());
+    }
+
+    #[test]
+    fn from_env_missing() {
+        assert!(from_env(Some(OsStr::new("carli-missing-pager -R"))).is_none());
+        assert!(from_env(Some(OsStr::new("./carli-missing-pager"))).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn from_env_shell() {
+        let command = from_env(Some(OsStr::new("LESS=R sh -s"))).unwrap();
+
+        assert_eq!(command.get_program(), "sh");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["-c", "LESS=R sh -s"]
+        );
+    }
+}
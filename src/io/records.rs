@@ -0,0 +1,325 @@
+//! Provides iterators that read a stream one record at a time.
+
+use super::Stream;
+use crate::error::{self, Error};
+use std::io::{self, BufRead, Read};
+
+/// The capacity of the buffer used to read ahead in the stream.
+const CAPACITY: usize = 64 * 1024;
+
+/// An iterator over fixed-size chunks of a stream.
+///
+/// This type is created by [`Stream::chunks`]. Every chunk is the requested size, except for the
+/// last chunk, which may be shorter. The chunk can be borrowed from the internal buffer using
+/// [`Chunks::read_chunk`], which avoids allocating a new buffer for every chunk.
+///
+/// The iterator reads ahead in the stream, so anything that has been read ahead but not returned
+/// is lost when the iterator is dropped.
+#[derive(Debug)]
+pub struct Chunks<'a> {
+    /// The buffer for the current chunk.
+    buffer: Vec<u8>,
+
+    /// The buffered stream being read.
+    reader: io::BufReader<&'a mut Stream>,
+
+    /// The size of each chunk.
+    size: usize,
+}
+
+impl<'a> Chunks<'a> {
+    /// Creates a new iterator for the stream.
+    pub(super) fn new(stream: &'a mut Stream, size: usize) -> Self {
+        assert!(size != 0, "The chunk size must not be zero.");
+
+        Self {
+            buffer: Vec::with_capacity(size),
+            reader: io::BufReader::with_capacity(CAPACITY, stream),
+            size,
+        }
+    }
+
+    /// Reads the next chunk into the internal buffer and returns it.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = b"abcde".to_vec().into();
+    /// let mut chunks = stream.chunks(2);
+    ///
+    /// assert_eq!(chunks.read_chunk().unwrap().unwrap(), b"ab");
+    /// assert_eq!(chunks.read_chunk().unwrap().unwrap(), b"cd");
+    /// assert_eq!(chunks.read_chunk().unwrap().unwrap(), b"e");
+    /// assert!(chunks.read_chunk().is_none());
+    /// # }
+    /// ```
+    pub fn read_chunk(&mut self) -> Option<error::Result<&[u8]>> {
+        self.buffer.clear();
+
+        let result = (&mut self.reader)
+            .take(self.size as u64)
+            .read_to_end(&mut self.buffer);
+
+        match result {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(&self.buffer)),
+            Err(error) => Some(Err(
+                Error::from(error).context("Could not read the next chunk from the stream.")
+            )),
+        }
+    }
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = error::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_chunk().map(|result| result.map(<[u8]>::to_vec))
+    }
+}
+
+/// An iterator over the lines of a stream.
+///
+/// This type is created by [`Stream::lines`]. Each line is returned without its line terminator,
+/// which is either `\n` or `\r\n`. The line can be borrowed from the internal buffer using
+/// [`Lines::read_line`], which avoids allocating a new string for every line.
+///
+/// The iterator reads ahead in the stream, so anything that has been read ahead but not returned
+/// is lost when the iterator is dropped.
+#[derive(Debug)]
+pub struct Lines<'a> {
+    /// The buffer for the current line.
+    buffer: Vec<u8>,
+
+    /// The number of the current line.
+    number: usize,
+
+    /// The buffered stream being read.
+    reader: io::BufReader<&'a mut Stream>,
+}
+
+impl<'a> Lines<'a> {
+    /// Creates a new iterator for the stream.
+    pub(super) fn new(stream: &'a mut Stream) -> Self {
+        Self {
+            buffer: Vec::new(),
+            number: 0,
+            reader: io::BufReader::with_capacity(CAPACITY, stream),
+        }
+    }
+
+    /// Reads the next line into the internal buffer and returns it.
+    ///
+    /// If the line is not valid UTF-8, an error is returned that includes the line number.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = b"first\nsecond\n".to_vec().into();
+    /// let mut lines = stream.lines();
+    ///
+    /// assert_eq!(lines.read_line().unwrap().unwrap(), "first");
+    /// assert_eq!(lines.read_line().unwrap().unwrap(), "second");
+    /// assert!(lines.read_line().is_none());
+    /// # }
+    /// ```
+    pub fn read_line(&mut self) -> Option<error::Result<&str>> {
+        self.buffer.clear();
+        self.number += 1;
+
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(error) => {
+                return Some(Err(Error::from(error).context(format!(
+                    "Could not read line {} from the stream.",
+                    self.number
+                ))))
+            }
+        }
+
+        if self.buffer.ends_with(b"\n") {
+            self.buffer.pop();
+
+            if self.buffer.ends_with(b"\r") {
+                self.buffer.pop();
+            }
+        }
+
+        Some(std::str::from_utf8(&self.buffer).map_err(|error| {
+            Error::from(error).context(format!(
+                "Line {} of the stream is not valid UTF-8.",
+                self.number
+            ))
+        }))
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = error::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_line().map(|result| result.map(str::to_string))
+    }
+}
+
+/// An iterator over the records of a stream that are separated by a delimiter.
+///
+/// This type is created by [`Stream::split`]. Each record is returned without the delimiter. The
+/// record can be borrowed from the internal buffer using [`Split::read_record`], which avoids
+/// allocating a new buffer for every record.
+///
+/// The iterator reads ahead in the stream, so anything that has been read ahead but not returned
+/// is lost when the iterator is dropped.
+#[derive(Debug)]
+pub struct Split<'a> {
+    /// The buffer for the current record.
+    buffer: Vec<u8>,
+
+    /// The byte that separates each record.
+    delimiter: u8,
+
+    /// The buffered stream being read.
+    reader: io::BufReader<&'a mut Stream>,
+}
+
+impl<'a> Split<'a> {
+    /// Creates a new iterator for the stream.
+    pub(super) fn new(stream: &'a mut Stream, delimiter: u8) -> Self {
+        Self {
+            buffer: Vec::new(),
+            delimiter,
+            reader: io::BufReader::with_capacity(CAPACITY, stream),
+        }
+    }
+
+    /// Reads the next record into the internal buffer and returns it.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = b"first\0second\0".to_vec().into();
+    /// let mut records = stream.split(b'\0');
+    ///
+    /// assert_eq!(records.read_record().unwrap().unwrap(), b"first");
+    /// assert_eq!(records.read_record().unwrap().unwrap(), b"second");
+    /// assert!(records.read_record().is_none());
+    /// # }
+    /// ```
+    pub fn read_record(&mut self) -> Option<error::Result<&[u8]>> {
+        self.buffer.clear();
+
+        match self.reader.read_until(self.delimiter, &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => {
+                if self.buffer.last() == Some(&self.delimiter) {
+                    self.buffer.pop();
+                }
+
+                Some(Ok(&self.buffer))
+            }
+            Err(error) => Some(Err(
+                Error::from(error).context("Could not read the next record from the stream.")
+            )),
+        }
+    }
+}
+
+impl Iterator for Split<'_> {
+    type Item = error::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().map(|result| result.map(<[u8]>::to_vec))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Inspect;
+
+    #[test]
+    fn chunks() {
+        let mut stream: Stream = b"abcdefg".to_vec().into();
+
+        let chunks = stream.chunks(3).collect::<error::Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            chunks,
+            vec![b"abc".to_vec(), b"def".to_vec(), b"g".to_vec()]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The chunk size must not be zero.")]
+    fn chunks_zero() {
+        let mut stream: Stream = Vec::new().into();
+
+        stream.chunks(0);
+    }
+
+    #[test]
+    fn lines() {
+        let mut stream: Stream = b"first\r\nsecond\n\nthird".to_vec().into();
+
+        let lines = stream.lines().collect::<error::Result<Vec<_>>>().unwrap();
+
+        assert_eq!(lines, vec!["first", "second", "", "third"]);
+    }
+
+    #[test]
+    fn lines_invalid_utf8() {
+        let mut stream: Stream = b"first\nsecond\n\xff\n".to_vec().into();
+        let mut lines = stream.lines();
+
+        assert_eq!(lines.next().unwrap().unwrap(), "first");
+        assert_eq!(lines.next().unwrap().unwrap(), "second");
+
+        let error = lines.next().unwrap().unwrap_err();
+
+        assert_eq!(
+            error.get_context(),
+            Some(vec!["Line 3 of the stream is not valid UTF-8."])
+        );
+        assert_eq!(error.get_status(), 1);
+    }
+
+    #[test]
+    fn lines_unsupported() {
+        let mut stream: Stream = io::stdout().into();
+
+        let error = stream.lines().next().unwrap().unwrap_err();
+
+        assert_eq!(
+            error.get_context(),
+            Some(vec!["Could not read line 1 from the stream."])
+        );
+        assert_eq!(
+            error.get_message(),
+            Some("The stream does not support reading.")
+        );
+    }
+
+    #[test]
+    fn split() {
+        let mut stream: Stream = b"first\0second\0\0third".to_vec().into();
+
+        let records = stream
+            .split(b'\0')
+            .collect::<error::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            records,
+            vec![
+                b"first".to_vec(),
+                b"second".to_vec(),
+                Vec::new(),
+                b"third".to_vec()
+            ]
+        );
+    }
+}
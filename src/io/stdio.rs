@@ -0,0 +1,299 @@
+//! Provides buffered writing to the standard error and output streams.
+//!
+//! Writing to [`io::Stdout`] directly requires acquiring its lock and, since it is line buffered,
+//! usually results in a system call for every line. For applications that produce a lot of output
+//! that cost adds up quickly. Instead, everything written is collected into a buffer that is shared
+//! by every [`super::Stream`] for the same standard stream, and only handed off to the locked
+//! standard stream when the buffer is flushed.
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Mutex, MutexGuard};
+
+/// The number of bytes buffered before they are written when using [`Buffering::Block`].
+const CAPACITY: usize = 8 * 1024;
+
+/// The shared buffer for the standard error stream.
+static STDERR: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// The shared buffer for the standard output stream.
+static STDOUT: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+/// The strategies for buffering writes to a standard stream.
+///
+/// By default, the standard output stream uses [`Buffering::Line`] when it is connected to a
+/// terminal and [`Buffering::Block`] otherwise. The standard error stream is not buffered.
+///
+/// Since the buffer is separate from the one used by [`io::Stdout`], anything written using
+/// [`println!`] may appear before what is still buffered here. What is still buffered is lost if
+/// the process exits using [`std::process::exit`] without calling [`flush_standard`] first, or if
+/// the stream is never dropped (e.g. because it is kept in a `static`).
+///
+/// ```
+/// use carli::io::{Buffering, Stream};
+/// use std::io;
+///
+/// # fn main() {
+/// let mut stream: Stream = io::stdout().into();
+///
+/// stream.set_buffering(Buffering::Block).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Buffering {
+    /// Writes once more than 8 KiB has been buffered.
+    ///
+    /// Anything less than that is only written when the stream is flushed or dropped, or when
+    /// [`flush_standard`] is called.
+    Block,
+
+    /// Writes at the end of every line.
+    Line,
+
+    /// Writes immediately.
+    Unbuffered,
+}
+
+/// The standard streams that can be written to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Target {
+    /// The standard error stream.
+    Stderr,
+
+    /// The standard output stream.
+    Stdout,
+}
+
+/// A buffered writer for a standard stream.
+///
+/// The buffer is flushed when the writer is dropped, but since [`std::process::exit`] does not
+/// run destructors, [`flush_standard`] should be called before exiting the process early.
+#[derive(Debug)]
+pub(super) struct Standard {
+    /// The strategy for buffering writes.
+    buffering: Buffering,
+
+    /// The standard stream being written to.
+    target: Target,
+}
+
+impl Drop for Standard {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+impl Standard {
+    /// Checks if the standard stream is connected to a terminal.
+    pub(super) fn is_terminal(&self) -> bool {
+        match self.target {
+            Target::Stderr => io::stderr().is_terminal(),
+            Target::Stdout => io::stdout().is_terminal(),
+        }
+    }
+
+    /// Creates a new writer using the default buffering strategy for the standard stream.
+    pub(super) fn new(target: Target) -> Self {
+        let buffering = match target {
+            Target::Stderr => Buffering::Unbuffered,
+            Target::Stdout if io::stdout().is_terminal() => Buffering::Line,
+            Target::Stdout => Buffering::Block,
+        };
+
+        Self { buffering, target }
+    }
+
+    /// Sets the strategy for buffering writes.
+    ///
+    /// Anything that has already been buffered is written first.
+    pub(super) fn set_buffering(&mut self, buffering: Buffering) -> io::Result<()> {
+        self.flush()?;
+        self.buffering = buffering;
+
+        Ok(())
+    }
+
+    /// Returns the shared buffer for the standard stream.
+    fn buffer(&self) -> MutexGuard<'static, Vec<u8>> {
+        lock(match self.target {
+            Target::Stderr => &STDERR,
+            Target::Stdout => &STDOUT,
+        })
+    }
+}
+
+impl io::Write for Standard {
+    fn flush(&mut self) -> io::Result<()> {
+        let mut buffer = self.buffer();
+
+        match self.target {
+            Target::Stderr => drain(&mut buffer, &mut io::stderr().lock()),
+            Target::Stdout => drain(&mut buffer, &mut io::stdout().lock()),
+        }
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let mut buffer = self.buffer();
+
+        match self.target {
+            Target::Stderr => write(&mut buffer, self.buffering, data, || io::stderr().lock()),
+            Target::Stdout => write(&mut buffer, self.buffering, data, || io::stdout().lock()),
+        }
+    }
+}
+
+/// Writes everything that has been buffered for the standard streams.
+///
+/// The buffers are normally written when a [`super::Stream`] using a standard stream is flushed or
+/// dropped. However, [`std::process::exit`] does not run destructors, so this function should be
+/// called before the process exits early. [`crate::error::Error::exit`] already does this.
+///
+/// ```
+/// use carli::io;
+///
+/// # fn main() {
+/// io::flush_standard().unwrap();
+///
+/// std::process::exit(0);
+/// # }
+/// ```
+pub fn flush_standard() -> io::Result<()> {
+    drain(&mut lock(&STDOUT), &mut io::stdout().lock())?;
+    drain(&mut lock(&STDERR), &mut io::stderr().lock())
+}
+
+/// Writes the buffer to the writer and then empties it.
+fn drain<W: io::Write>(buffer: &mut Vec<u8>, writer: &mut W) -> io::Result<()> {
+    if !buffer.is_empty() {
+        let result = writer.write_all(buffer);
+
+        buffer.clear();
+
+        result?;
+    }
+
+    writer.flush()
+}
+
+/// Acquires the lock for a shared buffer, even if another thread panicked while holding it.
+fn lock(buffer: &'static Mutex<Vec<u8>>) -> MutexGuard<'static, Vec<u8>> {
+    buffer.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Writes the data to the buffer, and then to the locked standard stream as required by the
+/// buffering strategy.
+///
+/// The standard stream is only locked if it needs to be written to.
+fn write<W, F>(
+    buffer: &mut Vec<u8>,
+    buffering: Buffering,
+    data: &[u8],
+    writer: F,
+) -> io::Result<usize>
+where
+    W: io::Write,
+    F: FnOnce() -> W,
+{
+    match buffering {
+        Buffering::Block => {
+            if buffer.len() + data.len() > CAPACITY {
+                buffer.extend_from_slice(data);
+
+                drain(buffer, &mut writer())?;
+            } else {
+                buffer.extend_from_slice(data);
+            }
+        }
+        Buffering::Line => {
+            buffer.extend_from_slice(data);
+
+            if data.contains(&b'\n') || buffer.len() >= CAPACITY {
+                drain(buffer, &mut writer())?;
+            }
+        }
+        Buffering::Unbuffered => {
+            buffer.extend_from_slice(data);
+
+            drain(buffer, &mut writer())?;
+        }
+    }
+
+    Ok(data.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A standard stream replacement that records what was written to it.
+    #[derive(Clone, Default)]
+    struct Recorder(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for Recorder {
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(data);
+
+            Ok(data.len())
+        }
+    }
+
+    #[test]
+    fn write_block() {
+        let mut buffer = Vec::new();
+        let recorder = Recorder::default();
+
+        write(&mut buffer, Buffering::Block, b"first\n", || {
+            recorder.clone()
+        })
+        .unwrap();
+
+        assert_eq!(buffer, b"first\n");
+        assert!(recorder.0.borrow().is_empty());
+
+        let data = vec![b'a'; CAPACITY];
+
+        write(&mut buffer, Buffering::Block, &data, || recorder.clone()).unwrap();
+
+        assert!(buffer.is_empty());
+        assert_eq!(recorder.0.borrow().len(), CAPACITY + 6);
+        assert!(recorder.0.borrow().starts_with(b"first\n"));
+    }
+
+    #[test]
+    fn write_line() {
+        let mut buffer = Vec::new();
+        let recorder = Recorder::default();
+
+        write(&mut buffer, Buffering::Line, b"first", || recorder.clone()).unwrap();
+
+        assert_eq!(buffer, b"first");
+        assert!(recorder.0.borrow().is_empty());
+
+        write(&mut buffer, Buffering::Line, b" line\n", || {
+            recorder.clone()
+        })
+        .unwrap();
+
+        assert!(buffer.is_empty());
+        assert_eq!(*recorder.0.borrow(), b"first line\n");
+    }
+
+    #[test]
+    fn write_unbuffered() {
+        let mut buffer = b"pending ".to_vec();
+        let recorder = Recorder::default();
+
+        write(&mut buffer, Buffering::Unbuffered, b"data", || {
+            recorder.clone()
+        })
+        .unwrap();
+
+        assert!(buffer.is_empty());
+        assert_eq!(*recorder.0.borrow(), b"pending data");
+    }
+}
@@ -10,6 +10,8 @@
 pub mod command;
 pub mod error;
 pub mod io;
+pub mod prompt;
+pub mod style;
 
 /// Provides preludes for different contexts in command line application development.
 pub mod prelude {
@@ -18,6 +20,11 @@ pub mod prelude {
         pub use crate::command::{Execute, Main};
         pub use crate::error::Result;
         pub use crate::io::{standard, Shared, Stream};
+
+        #[cfg(feature = "tokio")]
+        pub use crate::command::{AsyncExecute, AsyncMain};
+        #[cfg(feature = "tokio")]
+        pub use crate::io::{AsyncShared, AsyncStream, AsyncStreams};
     }
 
     /// A module to easily import APIs frequently used by subcommands.
@@ -25,12 +32,20 @@ pub mod prelude {
         pub use crate::command::Execute;
         pub use crate::err;
         pub use crate::error::{Context, Result};
-        pub use crate::io::Shared;
+        pub use crate::io::{Shared, SyncShared};
+
+        #[cfg(feature = "tokio")]
+        pub use crate::command::{AsyncExecute, BoxFuture};
+        #[cfg(feature = "tokio")]
+        pub use crate::io::AsyncShared;
     }
 
     /// A module to easily import frequently used testing APIs.
     pub mod test {
         pub use crate::error::Inspect;
-        pub use crate::io::memory;
+        pub use crate::io::{memory, memory_with_input, Faulty, Stream, Streams};
+
+        #[cfg(feature = "tokio")]
+        pub use crate::io::AsyncStreams;
     }
 }
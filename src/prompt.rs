@@ -0,0 +1,817 @@
+//! Provides prompts for asking the user questions.
+//!
+//! Each prompt writes its question to the error output stream and reads the answer from the input
+//! stream of a context (see [`crate::io::Shared`]), which keeps the global output stream free for
+//! the results of the command. Since only the streams of the context are used, the answers can be
+//! scripted using in-memory buffers when testing.
+//!
+//! ```no_run
+//! use carli::error::Result;
+//! use carli::io::Shared;
+//! use carli::prompt::Prompt;
+//!
+//! fn example(context: &dyn Shared) -> Result<()> {
+//!     let prompt = Prompt::new(context);
+//!
+//!     let name = prompt.input("What is your name?", Some("world"))?;
+//!     let count: u32 = prompt.number("How many greetings?", Some(1))?;
+//!
+//!     if prompt.confirm("Are you sure?", true)? {
+//!         for _ in 0..count {
+//!             println!("Hello, {}!", name);
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Prompting without a terminal
+//!
+//! When the input stream is not connected to a terminal, such as when the command is run by a
+//! script, there may not be anyone to answer the questions. What happens instead is chosen using
+//! [`NonInteractive`]. To script the answers in a test, the input stream can pretend to be a
+//! terminal using [`crate::io::Stream::set_terminal`].
+//!
+//! ```
+//! use carli::io::{self, Shared};
+//! use carli::prompt::Prompt;
+//!
+//! # fn main() {
+//! let streams = io::memory_with_input("yes\n");
+//!
+//! streams.input().set_terminal(true);
+//!
+//! assert!(Prompt::new(&streams).confirm("Continue?", false).unwrap());
+//! assert_eq!(streams.error_string(), "Continue? [y/N] ");
+//! # }
+//! ```
+
+mod terminal;
+
+use crate::error::{self, Error};
+use crate::io::Shared;
+use std::{fmt, io, str};
+
+/// The policies for answering a prompt when the input stream is not connected to a terminal.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NonInteractive {
+    /// Uses the default answer, or fails if the prompt does not have one.
+    #[default]
+    Default,
+
+    /// Fails without asking, even if the prompt has a default answer.
+    Fail,
+
+    /// Reads the answer from the input stream anyway, such as when the answers are piped in.
+    Read,
+}
+
+/// Asks the user questions using the streams of a context.
+///
+/// Every prompt that is asked again when the answer is not valid explains why on the error output
+/// stream. If the input stream ends before a valid answer is given, an error is returned.
+///
+/// ```
+/// use carli::error::Result;
+/// use carli::io::Shared;
+/// use carli::prompt::{NonInteractive, Prompt};
+///
+/// fn example(context: &dyn Shared) -> Result<()> {
+///     let prompt = Prompt::new(context).non_interactive(NonInteractive::Fail);
+///
+///     if prompt.confirm("Delete everything?", false)? {
+///         println!("Deleting everything...");
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct Prompt<'a> {
+    /// The context with the streams to use.
+    context: &'a dyn Shared,
+
+    /// The policy for prompting without a terminal.
+    policy: NonInteractive,
+}
+
+impl<'a> Prompt<'a> {
+    /// Asks a question that is answered with yes or no.
+    ///
+    /// An empty answer uses the default, which is shown in upper case in the prompt.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// assert!(Prompt::new(&streams).confirm("Continue?", true).unwrap());
+    /// assert_eq!(streams.error_string(), "Continue? [Y/n] ");
+    /// # }
+    /// ```
+    pub fn confirm(&self, question: &str, default: bool) -> error::Result<bool> {
+        let (hint, default) = if default {
+            ("[Y/n]", "y")
+        } else {
+            ("[y/N]", "n")
+        };
+
+        self.ask(
+            question,
+            format!("{} {} ", question, hint),
+            Some(default),
+            |answer| match answer.to_ascii_lowercase().as_str() {
+                "y" | "yes" => Ok(true),
+                "n" | "no" => Ok(false),
+                _ => Err(Error::new(1).message("Please answer yes or no.")),
+            },
+        )
+    }
+
+    /// Asks a question that is answered with any text.
+    ///
+    /// An empty answer uses the default if there is one, which is shown in the prompt.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("Alice\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let name = Prompt::new(&streams).input("Name?", Some("world")).unwrap();
+    ///
+    /// assert_eq!(name, "Alice");
+    /// assert_eq!(streams.error_string(), "Name? [world] ");
+    /// # }
+    /// ```
+    pub fn input(&self, question: &str, default: Option<&str>) -> error::Result<String> {
+        self.validate(question, default, |answer| Ok(answer.to_string()))
+    }
+
+    /// Asks a question for which any of the options may be chosen.
+    ///
+    /// The options are listed by number, and are chosen using a list of numbers that are separated
+    /// by commas. An empty answer chooses the options at the default indexes. The indexes of the
+    /// chosen options are returned in the order that they are listed.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("3, 1\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let chosen = Prompt::new(&streams)
+    ///     .multi_select("Toppings?", &["cheese", "ham", "olives"], &[])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(chosen, vec![0, 2]);
+    /// # }
+    /// ```
+    pub fn multi_select<T: fmt::Display>(
+        &self,
+        question: &str,
+        options: &[T],
+        defaults: &[usize],
+    ) -> error::Result<Vec<usize>> {
+        let default = defaults
+            .iter()
+            .map(|index| (index + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let prompt = if default.is_empty() {
+            format!("{}{}Choices: ", question, list(options))
+        } else {
+            format!("{}{}Choices [{}]: ", question, list(options), default)
+        };
+
+        self.ask(question, prompt, Some(&default), |answer| {
+            if answer.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let mut chosen = answer
+                .split(',')
+                .map(|choice| parse_choice(choice.trim(), options.len()))
+                .collect::<error::Result<Vec<_>>>()?;
+
+            chosen.sort_unstable();
+            chosen.dedup();
+
+            Ok(chosen)
+        })
+    }
+
+    /// Creates prompts using the streams of the context.
+    pub fn new(context: &'a dyn Shared) -> Self {
+        Self {
+            context,
+            policy: NonInteractive::default(),
+        }
+    }
+
+    /// Sets the policy for prompting when the input stream is not connected to a terminal.
+    ///
+    /// ```
+    /// use carli::error::Inspect;
+    /// use carli::io;
+    /// use carli::prompt::{NonInteractive, Prompt};
+    ///
+    /// # fn main() {
+    /// let streams = io::memory();
+    /// let prompt = Prompt::new(&streams).non_interactive(NonInteractive::Fail);
+    ///
+    /// let error = prompt.confirm("Continue?", true).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.get_message(),
+    ///     Some("Could not ask \"Continue?\" because the input is not interactive.")
+    /// );
+    /// # }
+    /// ```
+    pub fn non_interactive(mut self, policy: NonInteractive) -> Self {
+        self.policy = policy;
+
+        self
+    }
+
+    /// Asks a question that is answered with a number.
+    ///
+    /// The answer is parsed as the type of number that is expected, and asked again if it cannot
+    /// be. An empty answer uses the default if there is one, which is shown in the prompt.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("many\n3\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let count: u8 = Prompt::new(&streams).number("How many?", None).unwrap();
+    ///
+    /// assert_eq!(count, 3);
+    /// assert_eq!(
+    ///     streams.error_string(),
+    ///     "How many? Please enter a number.\nHow many? "
+    /// );
+    /// # }
+    /// ```
+    pub fn number<T>(&self, question: &str, default: Option<T>) -> error::Result<T>
+    where
+        T: fmt::Display + str::FromStr,
+    {
+        let default = default.map(|default| default.to_string());
+
+        let prompt = match &default {
+            Some(default) => format!("{} [{}] ", question, default),
+            None => format!("{} ", question),
+        };
+
+        self.ask(question, prompt, default.as_deref(), |answer| {
+            answer
+                .parse()
+                .map_err(|_| Error::new(1).message("Please enter a number."))
+        })
+    }
+
+    /// Asks a question that is answered with a secret, such as a password.
+    ///
+    /// When the input stream is connected to a terminal, what is typed is not shown, unless there
+    /// is a mask to show for each character instead. The terminal is restored to how it was
+    /// before, even if a panic occurs while reading. Pressing `Ctrl-C` returns an error with the
+    /// exit status code `130`, since the process is not ended by the terminal while the secret is
+    /// typed, and other control keys (e.g. `Ctrl-Z`) are ignored. If the input stream only
+    /// pretends to be a terminal, such as an in-memory buffer in a test, the answer is read as a
+    /// plain line.
+    ///
+    /// Since a secret does not have a default answer, an error is returned when the input stream
+    /// is not connected to a terminal, unless [`NonInteractive::Read`] is used.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("hunter2\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let password = Prompt::new(&streams).secret("Password:", None).unwrap();
+    ///
+    /// assert_eq!(password, "hunter2");
+    /// assert_eq!(streams.error_string(), "Password: ");
+    /// # }
+    /// ```
+    pub fn secret(&self, question: &str, mask: Option<char>) -> error::Result<String> {
+        self.check_interactive(question, false)?;
+
+        let hidden = terminal::NoEcho::new(&self.context.input())
+            .map_err(|error| Error::from(error).context("Could not hide the answer."))?;
+
+        self.write(&format!("{} ", question))?;
+
+        match hidden {
+            Some(hidden) => self.read_hidden(question, hidden, mask),
+            None => self.read_line()?.ok_or_else(|| unanswered(question)),
+        }
+    }
+
+    /// Asks a question for which one of the options must be chosen.
+    ///
+    /// The options are listed by number, and one is chosen using its number. An empty answer
+    /// chooses the option at the default index if there is one. The index of the chosen option is
+    /// returned.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("2\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let chosen = Prompt::new(&streams)
+    ///     .select("Color?", &["red", "green"], Some(0))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(chosen, 1);
+    /// assert_eq!(
+    ///     streams.error_string(),
+    ///     "Color?\n  1) red\n  2) green\nChoice [1]: "
+    /// );
+    /// # }
+    /// ```
+    pub fn select<T: fmt::Display>(
+        &self,
+        question: &str,
+        options: &[T],
+        default: Option<usize>,
+    ) -> error::Result<usize> {
+        let default = default.map(|index| (index + 1).to_string());
+
+        let prompt = match &default {
+            Some(default) => format!("{}{}Choice [{}]: ", question, list(options), default),
+            None => format!("{}{}Choice: ", question, list(options)),
+        };
+
+        self.ask(question, prompt, default.as_deref(), |answer| {
+            parse_choice(answer, options.len())
+        })
+    }
+
+    /// Asks a question that is answered with text that must be valid.
+    ///
+    /// The answer is given to the closure, which either returns the value for it, or an error
+    /// explaining why the answer is not valid. The message of the error is written to the error
+    /// output stream before the question is asked again. An empty answer uses the default if there
+    /// is one, which is also given to the closure.
+    ///
+    /// ```
+    /// use carli::io::{self, Shared};
+    /// use carli::err;
+    /// use carli::prompt::Prompt;
+    ///
+    /// # fn main() {
+    /// let streams = io::memory_with_input("bob\nBob\n");
+    ///
+    /// streams.input().set_terminal(true);
+    ///
+    /// let name = Prompt::new(&streams)
+    ///     .validate("Name?", None, |answer| {
+    ///         if !answer.starts_with(char::is_uppercase) {
+    ///             err!(1, "Names start with an upper case letter.");
+    ///         }
+    ///
+    ///         Ok(answer.to_string())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(name, "Bob");
+    /// # }
+    /// ```
+    pub fn validate<T, F>(
+        &self,
+        question: &str,
+        default: Option<&str>,
+        validate: F,
+    ) -> error::Result<T>
+    where
+        F: FnMut(&str) -> error::Result<T>,
+    {
+        let prompt = match default {
+            Some(default) => format!("{} [{}] ", question, default),
+            None => format!("{} ", question),
+        };
+
+        self.ask(question, prompt, default, validate)
+    }
+
+    /// Asks the question until the answer is parsed.
+    ///
+    /// An empty answer is replaced with the default answer, if there is one, before it is parsed.
+    fn ask<T, F>(
+        &self,
+        question: &str,
+        prompt: String,
+        default: Option<&str>,
+        mut parse: F,
+    ) -> error::Result<T>
+    where
+        F: FnMut(&str) -> error::Result<T>,
+    {
+        if !self.check_interactive(question, default.is_some())? {
+            if let Some(default) = default {
+                return parse(default);
+            }
+        }
+
+        loop {
+            self.write(&prompt)?;
+
+            let answer = self.read_line()?.ok_or_else(|| unanswered(question))?;
+
+            let answer = match answer.trim() {
+                "" => default.unwrap_or_default(),
+                answer => answer,
+            };
+
+            match parse(answer) {
+                Ok(value) => return Ok(value),
+                Err(error) => self.write(&error.to_string())?,
+            }
+        }
+    }
+
+    /// Checks if the question should be asked, applying the policy if the input is not interactive.
+    ///
+    /// If the question should not be asked, `false` is returned and the default answer is used.
+    fn check_interactive(&self, question: &str, has_default: bool) -> error::Result<bool> {
+        if self.context.input().is_terminal() {
+            return Ok(true);
+        }
+
+        match self.policy {
+            NonInteractive::Default if has_default => Ok(false),
+            NonInteractive::Default => Err(Error::new(1).message(format!(
+                "An answer to \"{}\" is required, but the input is not interactive.",
+                question
+            ))),
+            NonInteractive::Fail => Err(Error::new(1).message(format!(
+                "Could not ask \"{}\" because the input is not interactive.",
+                question
+            ))),
+            NonInteractive::Read => Ok(true),
+        }
+    }
+
+    /// Reads a secret from a terminal that does not show what is typed.
+    fn read_hidden(
+        &self,
+        question: &str,
+        hidden: terminal::NoEcho,
+        mask: Option<char>,
+    ) -> error::Result<String> {
+        use std::io::Read;
+
+        let mut byte = [0];
+        let mut secret = Vec::new();
+        let mut mask_buffer = [0; 4];
+        let mask = mask.map(|mask| &*mask.encode_utf8(&mut mask_buffer));
+
+        let result = loop {
+            let read = self.context.input().read(&mut byte);
+
+            match read {
+                Ok(0) if secret.is_empty() => break Err(unanswered(question)),
+                Ok(0) => break Ok(()),
+                Ok(_) => match byte[0] {
+                    b'\r' | b'\n' => break Ok(()),
+                    0x03 => break Err(Error::new(130).message("The prompt was interrupted.")),
+                    0x04 if secret.is_empty() => break Err(unanswered(question)),
+                    0x04 => (),
+                    0x08 | 0x7f => {
+                        if pop_char(&mut secret) && mask.is_some() {
+                            self.write("\x08 \x08")?;
+                        }
+                    }
+                    // Signals are disabled, so keys such as `Ctrl-Z` are read as control bytes.
+                    0x00..=0x1f => (),
+                    byte => {
+                        if let Some(mask) = mask {
+                            if !is_continuation(byte) {
+                                self.write(mask)?;
+                            }
+                        }
+
+                        secret.push(byte);
+                    }
+                },
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => (),
+                Err(error) => {
+                    break Err(Error::from(error).context("Could not read the answer."));
+                }
+            }
+        };
+
+        drop(hidden);
+
+        self.write("\n")?;
+
+        result?;
+
+        String::from_utf8(secret)
+            .map_err(|error| Error::from(error).context("The answer is not valid UTF-8."))
+    }
+
+    /// Reads the next line from the input stream, without the line ending.
+    ///
+    /// The line is read one byte at a time so that nothing after it is consumed, which leaves the
+    /// answers to any following prompts in the input stream. If the input stream has ended, then
+    /// [`None`] is returned.
+    fn read_line(&self) -> error::Result<Option<String>> {
+        use std::io::Read;
+
+        let mut input = self.context.input();
+        let mut byte = [0];
+        let mut line = Vec::new();
+
+        loop {
+            match input.read(&mut byte) {
+                Ok(0) if line.is_empty() => return Ok(None),
+                Ok(0) => break,
+                Ok(_) if byte[0] == b'\n' => break,
+                Ok(_) => line.push(byte[0]),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => (),
+                Err(error) => return Err(Error::from(error).context("Could not read the answer.")),
+            }
+        }
+
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+
+        String::from_utf8(line)
+            .map(Some)
+            .map_err(|error| Error::from(error).context("The answer is not valid UTF-8."))
+    }
+
+    /// Writes the text to the error output stream.
+    fn write(&self, text: &str) -> error::Result<()> {
+        use std::io::Write;
+
+        let mut error = self.context.error();
+
+        error
+            .write_all(text.as_bytes())
+            .and_then(|_| error.flush())
+            .map_err(|error| Error::from(error).context("Could not write the prompt."))
+    }
+}
+
+/// Checks if the byte continues a character that started with an earlier byte in UTF-8.
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xc0 == 0x80
+}
+
+/// Returns the options as a numbered list, with each option on its own line.
+fn list<T: fmt::Display>(options: &[T]) -> String {
+    options
+        .iter()
+        .enumerate()
+        .map(|(index, option)| format!("\n  {}) {}", index + 1, option))
+        .collect::<String>()
+        + "\n"
+}
+
+/// Removes the last character from the bytes of a string, returning `false` if there is none.
+fn pop_char(bytes: &mut Vec<u8>) -> bool {
+    while let Some(byte) = bytes.pop() {
+        if !is_continuation(byte) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Parses the number of a listed option into its index.
+fn parse_choice(answer: &str, count: usize) -> error::Result<usize> {
+    match answer.parse::<usize>() {
+        Ok(number) if (1..=count).contains(&number) => Ok(number - 1),
+        _ => Err(Error::new(1).message(format!("Please enter a number from 1 to {}.", count))),
+    }
+}
+
+/// Creates the error used when the input stream ended before the question was answered.
+fn unanswered(question: &str) -> Error {
+    Error::new(1).message(format!("No answer was given to \"{}\".", question))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::Inspect;
+    use crate::io::{self, Stream, Streams};
+
+    /// Creates streams with scripted answers that pretend to be interactive.
+    fn scripted(answers: &str) -> Streams {
+        let streams = io::memory_with_input(answers);
+
+        streams.input().set_terminal(true);
+
+        streams
+    }
+
+    #[test]
+    fn confirm() {
+        let streams = scripted("maybe\nY\nno\n\n");
+        let prompt = Prompt::new(&streams);
+
+        assert!(prompt.confirm("First?", false).unwrap());
+        assert!(!prompt.confirm("Second?", true).unwrap());
+        assert!(!prompt.confirm("Third?", false).unwrap());
+
+        assert_eq!(
+            streams.error_string(),
+            "First? [y/N] Please answer yes or no.\nFirst? [y/N] Second? [Y/n] Third? [y/N] "
+        );
+    }
+
+    #[test]
+    fn input() {
+        let streams = scripted("\r\n  Alice  \r\n");
+        let prompt = Prompt::new(&streams);
+
+        assert_eq!(prompt.input("First?", Some("world")).unwrap(), "world");
+        assert_eq!(prompt.input("Second?", None).unwrap(), "Alice");
+    }
+
+    #[test]
+    fn input_ended() {
+        let streams = scripted("");
+        let error = Prompt::new(&streams).input("Name?", None).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("No answer was given to \"Name?\".")
+        );
+    }
+
+    #[test]
+    fn input_not_consumed() {
+        let streams = io::Streams::with_input(Stream::queued(["yes\nAl", "ice\nrest"]));
+
+        streams.input().set_terminal(true);
+
+        let prompt = Prompt::new(&streams);
+
+        assert!(prompt.confirm("Continue?", false).unwrap());
+        assert_eq!(prompt.input("Name?", None).unwrap(), "Alice");
+        assert_eq!(streams.input().to_string().unwrap(), "rest");
+    }
+
+    #[test]
+    fn multi_select() {
+        let streams = scripted("4\n2,1,2\n\n");
+        let prompt = Prompt::new(&streams);
+        let options = ["a", "b", "c"];
+
+        assert_eq!(
+            prompt.multi_select("First?", &options, &[]).unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            prompt.multi_select("Second?", &options, &[2]).unwrap(),
+            vec![2]
+        );
+
+        assert_eq!(
+            streams.error_string(),
+            "First?\n  1) a\n  2) b\n  3) c\nChoices: Please enter a number from 1 to 3.\n\
+             First?\n  1) a\n  2) b\n  3) c\nChoices: \
+             Second?\n  1) a\n  2) b\n  3) c\nChoices [3]: "
+        );
+    }
+
+    #[test]
+    fn non_interactive_default() {
+        let streams = io::memory();
+        let prompt = Prompt::new(&streams);
+
+        assert!(prompt.confirm("Continue?", true).unwrap());
+        assert_eq!(prompt.input("Name?", Some("world")).unwrap(), "world");
+        assert_eq!(prompt.number::<u8>("Count?", Some(3)).unwrap(), 3);
+        assert_eq!(prompt.select("Color?", &["red"], Some(0)).unwrap(), 0);
+        assert_eq!(
+            prompt.multi_select("Colors?", &["red"], &[]).unwrap(),
+            vec![]
+        );
+
+        let error = prompt.input("Name?", None).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("An answer to \"Name?\" is required, but the input is not interactive.")
+        );
+        assert_eq!(streams.error_string(), "");
+    }
+
+    #[test]
+    fn non_interactive_fail() {
+        let streams = io::memory();
+        let prompt = Prompt::new(&streams).non_interactive(NonInteractive::Fail);
+
+        let error = prompt.input("Name?", Some("world")).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("Could not ask \"Name?\" because the input is not interactive.")
+        );
+        assert_eq!(error.get_status(), 1);
+    }
+
+    #[test]
+    fn non_interactive_read() {
+        let streams = io::memory_with_input("Alice\n");
+        let prompt = Prompt::new(&streams).non_interactive(NonInteractive::Read);
+
+        assert_eq!(prompt.input("Name?", Some("world")).unwrap(), "Alice");
+    }
+
+    #[test]
+    fn number() {
+        let streams = scripted("-1\n\n");
+        let prompt = Prompt::new(&streams);
+
+        assert_eq!(prompt.number::<i32>("First?", None).unwrap(), -1);
+        assert_eq!(prompt.number::<f64>("Second?", Some(1.5)).unwrap(), 1.5);
+    }
+
+    #[test]
+    fn secret() {
+        let streams = scripted("hunter2\r\n");
+        let prompt = Prompt::new(&streams);
+
+        assert_eq!(prompt.secret("Password:", Some('*')).unwrap(), "hunter2");
+        assert_eq!(streams.error_string(), "Password: ");
+
+        let streams = io::memory();
+        let error = Prompt::new(&streams).secret("Password:", None).unwrap_err();
+
+        assert_eq!(
+            error.get_message(),
+            Some("An answer to \"Password:\" is required, but the input is not interactive.")
+        );
+    }
+
+    #[test]
+    fn select() {
+        let streams = scripted("0\nred\n2\n");
+        let prompt = Prompt::new(&streams);
+
+        assert_eq!(prompt.select("Color?", &["red", "green"], None).unwrap(), 1);
+
+        assert_eq!(
+            streams
+                .error_string()
+                .matches("Please enter a number from 1 to 2.")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn validate() {
+        let streams = scripted("\nabc\n");
+        let prompt = Prompt::new(&streams);
+
+        let value = prompt
+            .validate("Code?", None, |answer| {
+                if answer.len() != 3 {
+                    crate::err!(1, "The code must be 3 characters.");
+                }
+
+                Ok(answer.to_uppercase())
+            })
+            .unwrap();
+
+        assert_eq!(value, "ABC");
+        assert_eq!(
+            streams.error_string(),
+            "Code? The code must be 3 characters.\nCode? "
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Provides control over how a terminal handles what is typed, which is used to read secrets.
+
+use crate::io::Stream;
+use std::io;
+
+/// A guard that keeps what is typed from being shown by a terminal.
+///
+/// Besides echo, line editing and signals are disabled, so that every byte can be read as soon as
+/// it is typed, and `Ctrl-C` is read as a byte instead of ending the process before the terminal
+/// could be restored. The original state of the terminal is restored when the guard is dropped,
+/// which also happens while unwinding from a panic.
+#[cfg(unix)]
+pub(super) struct NoEcho {
+    /// The file descriptor of the terminal.
+    fd: std::os::unix::io::RawFd,
+
+    /// The original state of the terminal.
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl Drop for NoEcho {
+    fn drop(&mut self) {
+        // SAFETY: The file descriptor is valid for as long as the input stream is, and the state
+        // was read from the same terminal.
+        unsafe {
+            libc::tcsetattr(self.fd, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+#[cfg(unix)]
+impl NoEcho {
+    /// Stops the terminal of the stream from showing what is typed.
+    ///
+    /// If the stream is not backed by a terminal, [`None`] is returned.
+    pub(super) fn new(stream: &Stream) -> io::Result<Option<Self>> {
+        let fd = match stream.raw_fd() {
+            // SAFETY: Checking a file descriptor that is not a terminal is harmless.
+            Some(fd) if unsafe { libc::isatty(fd) } == 1 => fd,
+            _ => return Ok(None),
+        };
+
+        // SAFETY: The state is plain data that is completely written by `tcgetattr`.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+
+        // SAFETY: The file descriptor is for a terminal, and the state is valid for writing.
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut hidden = original;
+
+        hidden.c_lflag &= !(libc::ECHO | libc::ECHONL | libc::ICANON | libc::ISIG);
+        hidden.c_cc[libc::VMIN] = 1;
+        hidden.c_cc[libc::VTIME] = 0;
+
+        // SAFETY: The file descriptor is for a terminal, and the state is valid for reading.
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &hidden) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Some(Self { fd, original }))
+    }
+}
+
+/// A guard that keeps what is typed from being shown by a terminal.
+///
+/// Terminals are only supported on Unix, so what is typed is read as a plain line elsewhere.
+#[cfg(not(unix))]
+pub(super) struct NoEcho;
+
+#[cfg(not(unix))]
+impl Drop for NoEcho {
+    fn drop(&mut self) {
+        // Nothing is restored, since the terminal was never changed.
+    }
+}
+
+#[cfg(not(unix))]
+impl NoEcho {
+    /// Always returns [`None`], since terminals are not supported.
+    pub(super) fn new(_stream: &Stream) -> io::Result<Option<Self>> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use crate::error::Inspect;
+    use crate::io::{Shared, Stream, Streams};
+    use crate::prompt::Prompt;
+    use std::ffi::CStr;
+    use std::io::{self, Read, Write};
+    use std::os::unix::io::FromRawFd;
+    use std::{fs, panic, thread};
+
+    /// A writer for the terminal that panics when it is asked to show a mask.
+    struct PanicOnMask(fs::File);
+
+    impl io::Write for PanicOnMask {
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.flush()
+        }
+
+        fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+            assert_ne!(buffer, b"*", "The mask could not be shown.");
+
+            self.0.write(buffer)
+        }
+    }
+
+    /// A pseudo-terminal, where the primary side types and reads what the terminal shows.
+    struct Terminal {
+        /// The side used to type and read what is shown.
+        primary: fs::File,
+
+        /// The side used as the terminal by the prompt.
+        secondary: fs::File,
+    }
+
+    impl Terminal {
+        /// Opens a new pseudo-terminal.
+        fn open() -> Self {
+            // SAFETY: Each call is checked for failure before its result is used, and the path of
+            // the secondary side is copied before anything else could change it.
+            unsafe {
+                let primary = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+
+                assert!(primary >= 0, "Could not open a pseudo-terminal.");
+                assert_eq!(libc::grantpt(primary), 0);
+                assert_eq!(libc::unlockpt(primary), 0);
+
+                let name = libc::ptsname(primary);
+
+                assert!(!name.is_null());
+
+                let path = CStr::from_ptr(name).to_str().unwrap().to_string();
+
+                Self {
+                    primary: fs::File::from_raw_fd(primary),
+                    secondary: fs::OpenOptions::new()
+                        .read(true)
+                        .write(true)
+                        .open(path)
+                        .unwrap(),
+                }
+            }
+        }
+
+        /// Checks if the terminal shows what is typed.
+        fn is_echo(&self) -> bool {
+            use std::os::unix::io::AsRawFd;
+
+            // SAFETY: The state is plain data that is completely written by `tcgetattr`.
+            let mut state: libc::termios = unsafe { std::mem::zeroed() };
+
+            // SAFETY: The file descriptor is for the terminal, and the state is valid for writing.
+            assert_eq!(
+                unsafe { libc::tcgetattr(self.secondary.as_raw_fd(), &mut state) },
+                0
+            );
+
+            state.c_lflag & libc::ECHO != 0
+        }
+
+        /// Creates streams that use the terminal for the error output and input streams.
+        fn streams(&self) -> Streams {
+            Streams::builder()
+                .error(self.secondary.try_clone().unwrap())
+                .input(self.secondary.try_clone().unwrap())
+                .output(Vec::new())
+                .build()
+        }
+
+        /// Types the keys once the prompt has been shown, and returns what was shown after it.
+        fn type_after(&self, prompt: &str, keys: &str) -> thread::JoinHandle<String> {
+            let mut primary = self.primary.try_clone().unwrap();
+            let prompt = prompt.to_string();
+            let keys = keys.to_string();
+
+            thread::spawn(move || {
+                let mut shown = Vec::new();
+                let mut buffer = [0; 64];
+
+                while !String::from_utf8_lossy(&shown).contains(&prompt) {
+                    let read = primary.read(&mut buffer).unwrap();
+
+                    shown.extend_from_slice(&buffer[..read]);
+                }
+
+                primary.write_all(keys.as_bytes()).unwrap();
+
+                let mut after = Vec::new();
+
+                while !after.ends_with(b"\n") {
+                    let read = primary.read(&mut buffer).unwrap();
+
+                    after.extend_from_slice(&buffer[..read]);
+                }
+
+                String::from_utf8_lossy(&after).to_string()
+            })
+        }
+    }
+
+    #[test]
+    fn secret_hidden() {
+        let terminal = Terminal::open();
+        let streams = terminal.streams();
+        let typist = terminal.type_after("Password: ", "hunter2\r");
+
+        assert!(streams.input().is_terminal());
+
+        let secret = Prompt::new(&streams).secret("Password:", None).unwrap();
+
+        assert_eq!(secret, "hunter2");
+        assert_eq!(typist.join().unwrap(), "\r\n");
+        assert!(terminal.is_echo());
+    }
+
+    #[test]
+    fn secret_interrupted() {
+        let terminal = Terminal::open();
+        let streams = terminal.streams();
+        let typist = terminal.type_after("Password: ", "abc\x03");
+
+        let error = Prompt::new(&streams).secret("Password:", None).unwrap_err();
+
+        assert_eq!(error.get_status(), 130);
+        assert_eq!(error.get_message(), Some("The prompt was interrupted."));
+        assert_eq!(typist.join().unwrap(), "\r\n");
+        assert!(terminal.is_echo());
+    }
+
+    #[test]
+    fn secret_masked() {
+        let terminal = Terminal::open();
+        let streams = terminal.streams();
+        let typist = terminal.type_after("PIN: ", "12\x7f\x1aé\x1c3\n");
+
+        let secret = Prompt::new(&streams).secret("PIN:", Some('*')).unwrap();
+
+        assert_eq!(secret, "1é3");
+        assert_eq!(typist.join().unwrap(), "**\x08 \x08**\r\n");
+        assert!(terminal.is_echo());
+    }
+
+    #[test]
+    fn secret_panicked() {
+        let terminal = Terminal::open();
+        let streams = Streams::builder()
+            .error(Stream::writer(PanicOnMask(
+                terminal.secondary.try_clone().unwrap(),
+            )))
+            .input(terminal.secondary.try_clone().unwrap())
+            .output(Vec::new())
+            .build();
+
+        let mut primary = terminal.primary.try_clone().unwrap();
+        let typist = thread::spawn(move || {
+            let mut shown = Vec::new();
+            let mut buffer = [0; 64];
+
+            while !shown.ends_with(b"PIN: ") {
+                let read = primary.read(&mut buffer).unwrap();
+
+                shown.extend_from_slice(&buffer[..read]);
+            }
+
+            primary.write_all(b"1").unwrap();
+        });
+
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            Prompt::new(&streams).secret("PIN:", Some('*'))
+        }));
+
+        typist.join().unwrap();
+
+        assert!(result.is_err());
+        assert!(terminal.is_echo());
+    }
+}
@@ -0,0 +1,620 @@
+//! Provides a means to style text written to output streams.
+//!
+//! Terminals support escape sequences that change how text is displayed, such as making it bold
+//! or changing its color. These sequences are only useful when a person is reading the output in
+//! a terminal, and simply get in the way when the output is piped to another program or written
+//! to a file. This module provides styles that are only applied when the [`Stream`] is connected
+//! to a terminal, while also respecting the conventions that let users opt in or out of color.
+//!
+//! ```no_run
+//! use carli::io::standard;
+//! use carli::outputln_styled;
+//! use carli::style::{Color, Style};
+//!
+//! # fn main() {
+//! let streams = standard();
+//! let style = Style::new().bold().fg(Color::Green);
+//!
+//! outputln_styled!(streams, style, "Hello, {}!", "world").unwrap();
+//! # }
+//! ```
+//!
+//! ### Deciding when to style
+//!
+//! When a stream uses [`ColorChoice::Auto`], which is the default, the following rules are used
+//! in order to decide if the text should be styled:
+//!
+//! 1. If `NO_COLOR` is set to a non-empty value, the text is not styled.
+//! 2. If `CLICOLOR_FORCE` is set to a value other than `0`, the text is styled.
+//! 3. If `TERM` is set to `dumb`, the text is not styled.
+//! 4. If the stream is connected to a terminal, the text is styled.
+//!
+//! The choice can be made for a stream using [`Stream::set_color`], or for every output stream
+//! using [`crate::io::Streams::set_color`]. This is especially useful when testing, where either
+//! the plain or the styled output may need to be verified.
+
+use crate::io::Stream;
+use std::{env, ffi, fmt, io};
+
+/// The escape sequence used to reset all styles.
+const RESET: &str = "\x1b[0m";
+
+/// The colors supported by terminals.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Color {
+    /// The color black.
+    Black,
+
+    /// The color blue.
+    Blue,
+
+    /// The color cyan.
+    Cyan,
+
+    /// The color green.
+    Green,
+
+    /// The color magenta.
+    Magenta,
+
+    /// The color red.
+    Red,
+
+    /// The color white.
+    White,
+
+    /// The color yellow.
+    Yellow,
+}
+
+impl Color {
+    /// Returns the offset added to the base foreground or background code.
+    fn offset(self) -> u8 {
+        match self {
+            Self::Black => 0,
+            Self::Red => 1,
+            Self::Green => 2,
+            Self::Yellow => 3,
+            Self::Blue => 4,
+            Self::Magenta => 5,
+            Self::Cyan => 6,
+            Self::White => 7,
+        }
+    }
+}
+
+/// The choices for when styles are applied to a stream.
+///
+/// ```
+/// use carli::io::Stream;
+/// use carli::style::ColorChoice;
+///
+/// # fn main() {
+/// let mut stream: Stream = Vec::new().into();
+///
+/// stream.set_color(ColorChoice::Always);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorChoice {
+    /// Always apply styles.
+    Always,
+
+    /// Apply styles if the stream is a terminal and the environment allows it.
+    #[default]
+    Auto,
+
+    /// Never apply styles.
+    Never,
+}
+
+impl ColorChoice {
+    /// Checks if styles should be applied to the given stream.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use carli::style::ColorChoice;
+    ///
+    /// # fn main() {
+    /// let stream: Stream = Vec::new().into();
+    ///
+    /// assert!(ColorChoice::Always.is_enabled(&stream));
+    /// assert!(!ColorChoice::Never.is_enabled(&stream));
+    /// # }
+    /// ```
+    pub fn is_enabled(self, stream: &Stream) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Auto => detect(
+                env::var_os("NO_COLOR"),
+                env::var_os("CLICOLOR_FORCE"),
+                env::var_os("TERM"),
+                stream.is_terminal(),
+            ),
+            Self::Never => false,
+        }
+    }
+}
+
+/// A collection of styles to apply to text.
+///
+/// A style is built by chaining the desired attributes together, and is then used to write text
+/// to a [`Stream`]. The style is only applied if the stream allows it.
+///
+/// ```
+/// use carli::io::Stream;
+/// use carli::style::{Color, ColorChoice, Style};
+///
+/// # fn main() {
+/// let mut stream: Stream = Vec::new().into();
+///
+/// stream.set_color(ColorChoice::Always);
+///
+/// Style::new()
+///     .bold()
+///     .fg(Color::Red)
+///     .writeln(&mut stream, format_args!("Hello, {}!", "world"))
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Style {
+    /// The background color.
+    bg: Option<Color>,
+
+    /// The flag used to make text bold.
+    bold: bool,
+
+    /// The flag used to make text dim.
+    dim: bool,
+
+    /// The foreground color.
+    fg: Option<Color>,
+
+    /// The flag used to make text italic.
+    italic: bool,
+
+    /// The flag used to underline text.
+    underline: bool,
+}
+
+impl Style {
+    /// Sets the background color.
+    ///
+    /// ```
+    /// use carli::style::{Color, Style};
+    ///
+    /// # fn main() {
+    /// let style = Style::new().bg(Color::Blue);
+    /// # }
+    /// ```
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+
+        self
+    }
+
+    /// Makes the text bold.
+    ///
+    /// ```
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let style = Style::new().bold();
+    /// # }
+    /// ```
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+
+        self
+    }
+
+    /// Makes the text dim.
+    ///
+    /// ```
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let style = Style::new().dim();
+    /// # }
+    /// ```
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+
+        self
+    }
+
+    /// Sets the foreground color.
+    ///
+    /// ```
+    /// use carli::style::{Color, Style};
+    ///
+    /// # fn main() {
+    /// let style = Style::new().fg(Color::Green);
+    /// # }
+    /// ```
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+
+        self
+    }
+
+    /// Makes the text italic.
+    ///
+    /// ```
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let style = Style::new().italic();
+    /// # }
+    /// ```
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+
+        self
+    }
+
+    /// Creates a new style without any attributes.
+    ///
+    /// ```
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let style = Style::new();
+    /// # }
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies the style to a value, regardless of where it will be written.
+    ///
+    /// Since the escape sequences are always included, this should only be used once it has been
+    /// determined that the destination supports them (e.g. with [`ColorChoice::is_enabled`]).
+    ///
+    /// ```
+    /// use carli::style::{Color, Style};
+    ///
+    /// # fn main() {
+    /// let painted = Style::new().fg(Color::Red).paint("error");
+    ///
+    /// assert_eq!(painted.to_string(), "\x1b[31merror\x1b[0m");
+    /// # }
+    /// ```
+    pub fn paint<T: fmt::Display>(self, value: T) -> Painted<T> {
+        Painted { style: self, value }
+    }
+
+    /// Makes the text underlined.
+    ///
+    /// ```
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let style = Style::new().underline();
+    /// # }
+    /// ```
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+
+        self
+    }
+
+    /// Writes formatted text to the stream using the style, if the stream allows it.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Vec::new().into();
+    ///
+    /// Style::new()
+    ///     .bold()
+    ///     .write(&mut stream, format_args!("Hello, world!"))
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn write(&self, stream: &mut Stream, args: fmt::Arguments<'_>) -> io::Result<()> {
+        use std::io::Write;
+
+        if stream.color().is_enabled(stream) {
+            write!(stream, "{}", self.paint(args))
+        } else {
+            stream.write_fmt(args)
+        }
+    }
+
+    /// Writes formatted text and a newline to the stream using the style, if the stream allows it.
+    ///
+    /// The newline is written after the style has been reset so that it does not carry over into
+    /// the next line of output.
+    ///
+    /// ```
+    /// use carli::io::Stream;
+    /// use carli::style::Style;
+    ///
+    /// # fn main() {
+    /// let mut stream: Stream = Vec::new().into();
+    ///
+    /// Style::new()
+    ///     .bold()
+    ///     .writeln(&mut stream, format_args!("Hello, world!"))
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn writeln(&self, stream: &mut Stream, args: fmt::Arguments<'_>) -> io::Result<()> {
+        use std::io::Write;
+
+        self.write(stream, args)?;
+
+        writeln!(stream)
+    }
+
+    /// Returns the codes for the escape sequence that enables the style.
+    fn codes(&self) -> Vec<u8> {
+        let mut codes = Vec::new();
+
+        if self.bold {
+            codes.push(1);
+        }
+
+        if self.dim {
+            codes.push(2);
+        }
+
+        if self.italic {
+            codes.push(3);
+        }
+
+        if self.underline {
+            codes.push(4);
+        }
+
+        if let Some(color) = self.fg {
+            codes.push(30 + color.offset());
+        }
+
+        if let Some(color) = self.bg {
+            codes.push(40 + color.offset());
+        }
+
+        codes
+    }
+}
+
+/// A value with a style applied to it.
+///
+/// When displayed, the value is surrounded by the escape sequences that enable and then reset the
+/// style. If the style has no attributes, the value is displayed as is.
+#[derive(Clone, Copy, Debug)]
+pub struct Painted<T> {
+    /// The style to apply.
+    style: Style,
+
+    /// The value to display.
+    value: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let codes = self.style.codes();
+
+        if codes.is_empty() {
+            return write!(f, "{}", self.value);
+        }
+
+        let codes = codes
+            .iter()
+            .map(|code| code.to_string())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        write!(f, "\x1b[{}m{}{}", codes, self.value, RESET)
+    }
+}
+
+/// Checks if styles should be applied using the environment and terminal state.
+fn detect(
+    no_color: Option<ffi::OsString>,
+    clicolor_force: Option<ffi::OsString>,
+    term: Option<ffi::OsString>,
+    terminal: bool,
+) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+
+    if term.is_some_and(|value| value == "dumb") {
+        return false;
+    }
+
+    terminal
+}
+
+/// Simplifies writing a styled, line terminated string to the error output.
+///
+/// ### Writing a simple message
+///
+/// ```
+/// use carli::errorln_styled;
+/// use carli::io::standard;
+/// use carli::style::{Color, Style};
+/// # fn main() {
+/// let streams = standard();
+///
+/// errorln_styled!(streams, Style::new().fg(Color::Red), "Writing to STDERR.").unwrap();
+/// # }
+/// ```
+///
+/// ### Writing a formatted message
+///
+/// ```
+/// use carli::errorln_styled;
+/// use carli::io::standard;
+/// use carli::style::{Color, Style};
+/// # fn main() {
+/// let streams = standard();
+///
+/// errorln_styled!(streams, Style::new().fg(Color::Red), "Writing to {}.", "STDERR").unwrap();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! errorln_styled {
+    ($context:expr, $style:expr, $message:expr) => {{
+        use $crate::io::Shared;
+
+        $style.writeln(&mut $context.error(), format_args!($message))
+    }};
+    ($context:expr, $style:expr, $message:expr, $($args:tt)*) => {{
+        use $crate::io::Shared;
+
+        $style.writeln(&mut $context.error(), format_args!($message, $($args)*))
+    }};
+}
+
+/// Simplifies writing a styled, line terminated string to the global output.
+///
+/// ### Writing a simple message
+///
+/// ```
+/// use carli::outputln_styled;
+/// use carli::io::standard;
+/// use carli::style::Style;
+/// # fn main() {
+/// let streams = standard();
+///
+/// outputln_styled!(streams, Style::new().bold(), "Writing to STDOUT.").unwrap();
+/// # }
+/// ```
+///
+/// ### Writing a formatted message
+///
+/// ```
+/// use carli::outputln_styled;
+/// use carli::io::standard;
+/// use carli::style::Style;
+/// # fn main() {
+/// let streams = standard();
+///
+/// outputln_styled!(streams, Style::new().bold(), "Writing to {}.", "STDOUT").unwrap();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! outputln_styled {
+    ($context:expr, $style:expr, $message:expr) => {{
+        use $crate::io::Shared;
+
+        $style.writeln(&mut $context.output(), format_args!($message))
+    }};
+    ($context:expr, $style:expr, $message:expr, $($args:tt)*) => {{
+        use $crate::io::Shared;
+
+        $style.writeln(&mut $context.output(), format_args!($message, $($args)*))
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::io::memory;
+
+    #[test]
+    fn color_choice_always() {
+        let stream: Stream = Vec::new().into();
+
+        assert!(ColorChoice::Always.is_enabled(&stream));
+    }
+
+    #[test]
+    fn color_choice_never() {
+        let mut stream: Stream = Vec::new().into();
+
+        stream.set_terminal(true);
+
+        assert!(!ColorChoice::Never.is_enabled(&stream));
+    }
+
+    #[test]
+    fn detect_clicolor_force() {
+        assert!(detect(None, Some("1".into()), None, false));
+        assert!(!detect(None, Some("0".into()), None, false));
+        assert!(!detect(Some("1".into()), Some("1".into()), None, true));
+    }
+
+    #[test]
+    fn detect_no_color() {
+        assert!(!detect(Some("1".into()), None, None, true));
+        assert!(detect(Some("".into()), None, None, true));
+    }
+
+    #[test]
+    fn detect_term_dumb() {
+        assert!(!detect(None, None, Some("dumb".into()), true));
+        assert!(detect(None, None, Some("xterm".into()), true));
+    }
+
+    #[test]
+    fn detect_terminal() {
+        assert!(detect(None, None, None, true));
+        assert!(!detect(None, None, None, false));
+    }
+
+    #[test]
+    fn errorln_styled_message() {
+        let streams = memory();
+
+        streams.set_color(ColorChoice::Always);
+
+        errorln_styled!(streams, Style::new().fg(Color::Red), "test").unwrap();
+
+        assert_eq!(streams.error_string(), "\x1b[31mtest\x1b[0m\n");
+    }
+
+    #[test]
+    fn outputln_styled_message_formatted() {
+        let streams = memory();
+
+        streams.set_color(ColorChoice::Always);
+
+        outputln_styled!(streams, Style::new().bold(), "test {}", "message").unwrap();
+
+        assert_eq!(streams.output_string(), "\x1b[1mtest message\x1b[0m\n");
+    }
+
+    #[test]
+    fn outputln_styled_plain() {
+        let streams = memory();
+
+        streams.set_color(ColorChoice::Never);
+
+        outputln_styled!(streams, Style::new().bold(), "test").unwrap();
+
+        assert_eq!(streams.output_string(), "test\n");
+    }
+
+    #[test]
+    fn paint_all_attributes() {
+        let style = Style::new()
+            .bold()
+            .dim()
+            .italic()
+            .underline()
+            .fg(Color::White)
+            .bg(Color::Black);
+
+        assert_eq!(
+            style.paint("test").to_string(),
+            "\x1b[1;2;3;4;37;40mtest\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn paint_without_attributes() {
+        assert_eq!(Style::new().paint("test").to_string(), "test");
+    }
+}